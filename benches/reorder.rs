@@ -0,0 +1,56 @@
+//! The benchmark compares the hash map based `reorder_enumerated()` to the ring buffer
+//! based `reorder_enumerated_dense()` on indices shuffled within a small window, which
+//! resembles the output of parallel unordered tasks.
+//!
+//! Run it with `cargo bench --bench reorder`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use futures::{executor::block_on, stream, stream::StreamExt as _};
+use par_stream::prelude::*;
+use rand::prelude::*;
+
+const LEN: usize = 100_000;
+const WINDOW: usize = 64;
+
+/// Creates the `(index, item)` pairs with indices shuffled within each window.
+fn shuffled_items() -> Vec<(usize, usize)> {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut indices: Vec<_> = (0..LEN).collect();
+    indices
+        .chunks_mut(WINDOW)
+        .for_each(|chunk| chunk.shuffle(&mut rng));
+    indices.into_iter().map(|index| (index, index)).collect()
+}
+
+fn reorder(c: &mut Criterion) {
+    let items = shuffled_items();
+    let mut group = c.benchmark_group("reorder");
+    group.throughput(Throughput::Elements(LEN as u64));
+
+    group.bench_function("hash_map", |b| {
+        b.iter_batched(
+            || items.clone(),
+            |items| {
+                let count = block_on(stream::iter(items).reorder_enumerated().count());
+                assert_eq!(count, LEN);
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("ring_buffer", |b| {
+        b.iter_batched(
+            || items.clone(),
+            |items| {
+                let count = block_on(stream::iter(items).reorder_enumerated_dense().count());
+                assert_eq!(count, LEN);
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, reorder);
+criterion_main!(benches);
@@ -2,6 +2,7 @@ use crate::{
     common::*, config::BufSize, index_stream::IndexStreamExt as _, rt, stream::StreamExt as _,
     utils,
 };
+use std::sync::Mutex;
 use tokio::sync::{oneshot, watch};
 
 /// The build type returned from [broadcast()](crate::par_stream::ParStreamExt::broadcast).
@@ -10,9 +11,12 @@ use tokio::sync::{oneshot, watch};
 /// of items of the stream. The builder is finished by `guard.build()` so that
 /// registered receivers can start consuming data. Otherwise, the receivers
 /// take empty input.
+///
+/// The broadcasting worker is aborted once the builder and all receivers are dropped.
 #[derive(Debug)]
 pub struct BroadcastBuilder<T> {
     pub(super) buf_size: Option<usize>,
+    pub(super) task: Arc<Mutex<rt::JoinHandle<()>>>,
     pub(super) ready_rx: watch::Receiver<()>,
     pub(super) senders_tx: Option<oneshot::Sender<Vec<flume::Sender<(usize, T)>>>>,
     pub(super) senders: Option<Vec<flume::Sender<(usize, T)>>>,
@@ -30,7 +34,7 @@ where
         let (senders_tx, senders_rx) = oneshot::channel();
         let (ready_tx, ready_rx) = watch::channel(());
 
-        rt::spawn(async move {
+        let task = rt::spawn(async move {
             // wait for receiver list to be ready
             let senders: Vec<flume::Sender<(usize, T)>> = match senders_rx.await {
                 Ok(senders) => senders,
@@ -95,10 +99,12 @@ where
                     let _ = stream.enumerate().map(Ok).forward(sink).await;
                 }
             }
-        });
+        })
+        .abort_on_drop();
 
         BroadcastBuilder {
             buf_size: buf_size.into().get(),
+            task: Arc::new(Mutex::new(task)),
             ready_rx,
             senders_tx: Some(senders_tx),
             senders: Some(vec![]),
@@ -109,6 +115,7 @@ where
     pub fn register(&mut self) -> BroadcastStream<T> {
         let Self {
             buf_size,
+            ref task,
             ref ready_rx,
             ref mut senders,
             ..
@@ -125,7 +132,10 @@ where
             .wait_until(async move { ready_rx.changed().await.is_ok() })
             .boxed();
 
-        BroadcastStream { stream }
+        BroadcastStream {
+            stream,
+            _task: task.clone(),
+        }
     }
 
     /// Finish the builder to start broadcasting.
@@ -141,6 +151,7 @@ where
 pub struct BroadcastStream<T> {
     #[pin]
     pub(super) stream: BoxStream<'static, T>,
+    pub(super) _task: Arc<Mutex<rt::JoinHandle<()>>>,
 }
 
 impl<T> Stream for BroadcastStream<T> {
@@ -7,17 +7,20 @@ pub use fn_factory::*;
 pub use future_factory::*;
 
 use crate::{
+    cancel::{CancellationToken, Cancelled},
     common::*,
     config::ParParams,
     index_stream::{IndexStreamExt as _, ReorderEnumerated},
-    par_stream::ParStreamExt as _,
+    par_stream::spawned_on,
     rt, utils,
 };
 use flume::r#async::RecvStream;
+use futures::stream::TakeUntil;
 use tokio::sync::broadcast;
 
 pub type UnorderedStream<T> = RecvStream<'static, T>;
-pub type OrderedStream<T> = ReorderEnumerated<RecvStream<'static, (usize, T)>, T>;
+pub type OrderedStream<T> =
+    TakeUntil<ReorderEnumerated<RecvStream<'static, (usize, T)>, T>, Cancelled>;
 
 /// Parallel stream builder created by [par_builder](crate::par_stream::ParStreamExt::par_builder).
 pub struct ParBuilder<St>
@@ -184,18 +187,30 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
 
-        let stream = stream.map(move |item| fac.generate(item)).spawned(buf_size);
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            stream
+                .take_until(cancel.cancelled())
+                .map(move |item| fac.generate(item)),
+        );
         let (output_tx, output_rx) = utils::channel(buf_size);
 
         (0..num_workers).for_each(move |_| {
             let stream = stream.clone();
             let output_tx = output_tx.clone();
+            let cancelled = cancel.cancelled();
 
-            rt::spawn(async move {
+            rt::spawn_on(runtime.as_ref(), async move {
                 let _ = stream
                     .then(|fut| fut)
+                    .take_until(cancelled)
                     .map(Ok)
                     .forward(output_tx.into_sink())
                     .await;
@@ -217,28 +232,41 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
 
-        let stream = stream
-            .map(move |item| fac.generate(item))
-            .enumerate()
-            .spawned(buf_size);
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            stream
+                .take_until(cancel.cancelled())
+                .map(move |item| fac.generate(item))
+                .enumerate(),
+        );
         let (output_tx, output_rx) = utils::channel(buf_size);
 
-        (0..num_workers).for_each(move |_| {
+        (0..num_workers).for_each(|_| {
             let stream = stream.clone();
             let output_tx = output_tx.clone();
+            let cancelled = cancel.cancelled();
 
-            rt::spawn(async move {
+            rt::spawn_on(runtime.as_ref(), async move {
                 let _ = stream
                     .then(|(index, fut)| async move { (index, fut.await) })
+                    .take_until(cancelled)
                     .map(Ok)
                     .forward(output_tx.into_sink())
                     .await;
             });
         });
 
-        output_rx.into_stream().reorder_enumerated()
+        output_rx
+            .into_stream()
+            .reorder_enumerated()
+            .take_until(cancel.cancelled())
     }
 }
 
@@ -257,17 +285,32 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
         let Self {
             mut fac, stream, ..
         } = self;
-        let stream = stream.map(move |item| fac.generate(item)).spawned(buf_size);
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            stream
+                .take_until(cancel.cancelled())
+                .map(move |item| fac.generate(item)),
+        );
 
         let worker_futures = (0..num_workers).map(move |_| {
             let stream = stream.clone();
+            let cancelled = cancel.cancelled();
 
-            rt::spawn(async move {
-                stream.for_each(|fut| fut).await;
+            rt::spawn_on(runtime.as_ref(), async move {
+                stream
+                    .then(|fut| fut)
+                    .take_until(cancelled)
+                    .for_each(future::ready)
+                    .await;
             })
         });
 
@@ -291,27 +334,48 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            abort_on_error,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
+        let abort = CancellationToken::new();
         let Self {
             mut fac, stream, ..
         } = self;
         let (terminate_tx, mut terminate_rx) = broadcast::channel(1);
-        let stream = stream
-            .take_until(async move {
-                let _ = terminate_rx.recv().await;
-            })
-            .map(move |item| fac.generate(item))
-            .spawned(buf_size);
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            stream
+                .take_until(async move {
+                    let _ = terminate_rx.recv().await;
+                })
+                .take_until(cancel.cancelled())
+                .map(move |item| fac.generate(item)),
+        );
 
         let worker_futures = (0..num_workers).map(move |_| {
             let stream = stream.clone();
             let terminate_tx = terminate_tx.clone();
+            let cancelled = cancel.cancelled();
+            let abort = abort.clone();
 
-            rt::spawn(async move {
-                let result = stream.map(Ok).try_for_each(|fut| fut).await;
+            rt::spawn_on(runtime.as_ref(), async move {
+                let result = stream
+                    .then(|fut| fut)
+                    .take_until(cancelled)
+                    .take_until(abort.cancelled())
+                    .try_for_each(|()| future::ok(()))
+                    .await;
 
                 if result.is_err() {
                     let _ = terminate_tx.send(());
+
+                    if abort_on_error {
+                        abort.cancel();
+                    }
                 }
 
                 result
@@ -393,17 +457,31 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
 
-        let stream = stream.map(move |item| fac.generate(item)).spawned(buf_size);
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            stream
+                .take_until(cancel.cancelled())
+                .map(move |item| fac.generate(item)),
+        );
         let (output_tx, output_rx) = utils::channel(buf_size);
 
         (0..num_workers).for_each(move |_| {
             let mut stream = stream.clone();
             let output_tx = output_tx.clone();
+            let cancel = cancel.clone();
 
-            rt::spawn_blocking(move || {
+            rt::spawn_blocking_on(runtime.as_ref(), move || {
                 while let Some(func) = rt::block_on(stream.next()) {
+                    if cancel.is_cancelled() {
+                        break;
+                    }
                     let output = func();
                     let result = output_tx.send(output);
                     if result.is_err() {
@@ -428,20 +506,32 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
 
-        let stream = stream
-            .map(move |item| fac.generate(item))
-            .enumerate()
-            .spawned(buf_size);
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            stream
+                .take_until(cancel.cancelled())
+                .map(move |item| fac.generate(item))
+                .enumerate(),
+        );
         let (output_tx, output_rx) = utils::channel(buf_size);
 
-        (0..num_workers).for_each(move |_| {
+        (0..num_workers).for_each(|_| {
             let mut stream = stream.clone();
             let output_tx = output_tx.clone();
+            let cancel = cancel.clone();
 
-            rt::spawn_blocking(move || {
+            rt::spawn_blocking_on(runtime.as_ref(), move || {
                 while let Some((index, func)) = rt::block_on(stream.next()) {
+                    if cancel.is_cancelled() {
+                        break;
+                    }
                     let output = func();
                     let result = output_tx.send((index, output));
                     if result.is_err() {
@@ -451,7 +541,10 @@ where
             });
         });
 
-        output_rx.into_stream().reorder_enumerated()
+        output_rx
+            .into_stream()
+            .reorder_enumerated()
+            .take_until(cancel.cancelled())
     }
 }
 
@@ -473,14 +566,28 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
-        let stream = stream.map(move |item| fac.generate(item)).spawned(buf_size);
+        let cancel = cancel.unwrap_or_default();
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            stream
+                .take_until(cancel.cancelled())
+                .map(move |item| fac.generate(item)),
+        );
 
         let worker_futures = (0..num_workers).map(move |_| {
             let mut stream = stream.clone();
+            let cancel = cancel.clone();
 
-            rt::spawn_blocking(move || {
+            rt::spawn_blocking_on(runtime.as_ref(), move || {
                 while let Some(func) = rt::block_on(stream.next()) {
+                    if cancel.is_cancelled() {
+                        break;
+                    }
                     func();
                 }
             })
@@ -681,7 +788,10 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::async_test;
+    use crate::{
+        par_stream::ParStreamExt as _,
+        utils::{async_test, DropGuard},
+    };
 
     async_test! {
         async fn par_builder_blocking_test() {
@@ -766,6 +876,48 @@ mod tests {
             }
         }
 
+        async fn par_builder_try_for_each_abort_test() {
+            let params = ParParams {
+                num_workers: 4,
+                abort_on_error: true,
+                ..ParParams::default()
+            };
+            let (mut guards, receivers): (Vec<_>, Vec<_>) = (0..4)
+                .map(|_| DropGuard::new())
+                .map(|(guard, rx)| (Some(guard), rx))
+                .unzip();
+            let (started_tx, started_rx) = flume::unbounded();
+
+            let result = stream::iter(0..4)
+                .par_builder()
+                .map_async(move |value: usize| {
+                    let mut guard = guards[value].take().unwrap();
+                    let started_tx = started_tx.clone();
+                    let started_rx = started_rx.clone();
+
+                    async move {
+                        // fails once the other items are in flight
+                        if value == 0 {
+                            started_rx.stream().take(3).count().await;
+                            return Err(value);
+                        }
+
+                        started_tx.send(()).unwrap();
+                        rt::sleep(Duration::from_secs(5)).await;
+                        guard.finish();
+                        Ok(())
+                    }
+                })
+                .try_for_each(params)
+                .await;
+            assert_eq!(result, Err(0));
+
+            // the in-flight futures are dropped before they finish
+            for rx in receivers {
+                assert_eq!(rx.await, Ok(false));
+            }
+        }
+
         // #[tokio::test]
         // async fn par_unfold_builder_async_test() {
         //     let vec: Vec<_> = super::par_unfold_builder(|| async move {
@@ -0,0 +1,135 @@
+use crate::common::*;
+use tokio::sync::Notify;
+
+/// A handle to cooperatively cancel parallel workers.
+///
+/// The token is cheap to clone and all clones share the same state. It can be passed
+/// to parallel combinators via the `cancel` field of [ParParams](crate::ParParams).
+/// Once [cancel()](CancellationToken::cancel) is called, the workers stop taking input
+/// items, in-flight futures are dropped and the output streams terminate.
+///
+/// ```rust
+/// # par_stream::rt::block_on_executor(async move {
+/// use futures::prelude::*;
+/// use par_stream::{prelude::*, CancellationToken, ParParams};
+///
+/// let token = CancellationToken::new();
+/// let params = ParParams {
+///     cancel: Some(token.clone()),
+///     ..ParParams::default()
+/// };
+///
+/// let mut stream = stream::iter(0..).par_then(params, |value| async move { value * 2 });
+/// assert_eq!(stream.next().await, Some(0));
+///
+/// token.cancel();
+/// assert!(stream.next().await.is_none());
+/// # })
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    is_cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    /// Creates a new token which is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the token and wakes up all tasks waiting on it.
+    ///
+    /// Calling this method more than once has no effect.
+    pub fn cancel(&self) {
+        if !self.inner.is_cancelled.swap(true, SeqCst) {
+            self.inner.notify.notify_waiters();
+        }
+    }
+
+    /// Returns `true` if the token is cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled.load(SeqCst)
+    }
+
+    /// Creates a future that resolves once the token is cancelled.
+    pub fn cancelled(&self) -> Cancelled {
+        let inner = self.inner.clone();
+
+        let future = async move {
+            let notified = inner.notify.notified();
+
+            if !inner.is_cancelled.load(SeqCst) {
+                notified.await;
+            }
+        }
+        .boxed();
+
+        Cancelled { future }
+    }
+}
+
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for CancellationToken {}
+
+impl Hash for CancellationToken {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        Arc::as_ptr(&self.inner).hash(state);
+    }
+}
+
+/// Future for the [cancelled()](CancellationToken::cancelled) method.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Cancelled {
+    future: BoxFuture<'static, ()>,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.future.poll_unpin(cx)
+    }
+}
+
+impl Debug for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cancelled").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rt, utils::async_test};
+
+    async_test! {
+        async fn cancellation_token_test() {
+            let token = CancellationToken::new();
+            assert!(!token.is_cancelled());
+
+            let waiter = rt::spawn(token.cancelled());
+            rt::sleep(Duration::from_millis(50)).await;
+
+            token.clone().cancel();
+            waiter.await;
+            assert!(token.is_cancelled());
+
+            // a future created after cancellation resolves immediately
+            token.cancelled().await;
+        }
+    }
+}
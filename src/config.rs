@@ -1,4 +1,4 @@
-use crate::common::*;
+use crate::{cancel::CancellationToken, common::*, rt::RuntimeHandle};
 
 /// The default value returned by [get_buf_size_scale()].
 pub const DEFAULT_BUF_SIZE_SCALE: f64 = 2.0;
@@ -74,6 +74,11 @@ mod config_ {
                     ParParams {
                         num_workers,
                         buf_size,
+                        cancel: None,
+                        panic_policy: PanicPolicy::default(),
+                        abort_on_error: false,
+                        reorder_capacity: None,
+                        runtime: None,
                     }
                 }
                 Self::FixedWorkers { num_workers } => {
@@ -82,6 +87,11 @@ mod config_ {
                     ParParams {
                         num_workers,
                         buf_size,
+                        cancel: None,
+                        panic_policy: PanicPolicy::default(),
+                        abort_on_error: false,
+                        reorder_capacity: None,
+                        runtime: None,
                     }
                 }
                 Self::ScaleOfCpus { scale } => {
@@ -91,6 +101,11 @@ mod config_ {
                     ParParams {
                         num_workers,
                         buf_size,
+                        cancel: None,
+                        panic_policy: PanicPolicy::default(),
+                        abort_on_error: false,
+                        reorder_capacity: None,
+                        runtime: None,
                     }
                 }
                 Self::Manual {
@@ -103,6 +118,11 @@ mod config_ {
                     ParParams {
                         num_workers,
                         buf_size,
+                        cancel: None,
+                        panic_policy: PanicPolicy::default(),
+                        abort_on_error: false,
+                        reorder_capacity: None,
+                        runtime: None,
                     }
                 }
             }
@@ -200,11 +220,34 @@ pub use params::*;
 mod params {
     use super::*;
 
-    /// The parameters including `num_workers` and `buf_size`.
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    /// The parameters including `num_workers`, `buf_size`, an optional cancellation token
+    /// and the policies on panics and errors.
+    ///
+    /// The type is not `Copy` because the cancellation token is shared. Clone it to reuse
+    /// the parameters for more than one combinator.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     pub struct ParParams {
         pub num_workers: usize,
         pub buf_size: Option<usize>,
+        /// Stops the workers once the token is cancelled.
+        pub cancel: Option<CancellationToken>,
+        /// Determines how a panic in a parallel task is handled.
+        pub panic_policy: PanicPolicy,
+        /// Drops the in-flight tasks of `try_par_for_each()` and
+        /// [ParAsyncBuilder::try_for_each()](crate::builder::ParAsyncBuilder::try_for_each)
+        /// as soon as an error occurs, and those of `try_par_for_each_with_error_policy()` as
+        /// soon as the policy stops, instead of waiting for them to complete.
+        pub abort_on_error: bool,
+        /// Bounds the number of items in flight or waiting for reordering in ordered
+        /// combinators such as [par_then()](crate::ParStreamExt::par_then).
+        ///
+        /// An input item is not taken until it is within `capacity` items from the next item
+        /// to be yielded, so that the reorder buffer never exceeds the capacity. The bound is
+        /// applied when taking input because a full reorder buffer cannot stop polling the
+        /// workers without waiting for the missing item forever.
+        pub reorder_capacity: Option<usize>,
+        /// Spawns the workers on the runtime instead of the default one.
+        pub runtime: Option<RuntimeHandle>,
     }
 
     impl Default for ParParams {
@@ -237,3 +280,57 @@ mod params {
         }
     }
 }
+
+pub use panic_policy::*;
+mod panic_policy {
+    /// The policy on a panic raised by a parallel task.
+    ///
+    /// Use [par_then_catch_unwind()](crate::ParStreamExt::par_then_catch_unwind) and its
+    /// relatives to convert panics to `Err(PanicPayload)` items instead.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub enum PanicPolicy {
+        /// Resumes the panic on the consumer of the output stream at the position of the item.
+        #[default]
+        Propagate,
+        /// Discards the item which task panics.
+        Skip,
+    }
+}
+
+pub use error_policy::*;
+mod error_policy {
+    /// The policy on errors produced by fallible parallel combinators.
+    ///
+    /// It is accepted by [try_par_then_with_error_policy()](crate::TryParStreamExt::try_par_then_with_error_policy)
+    /// and its relatives. The `try_par_*` combinators without a policy argument are fail-fast.
+    #[derive(Debug, Clone, Copy, PartialEq, Default)]
+    pub enum ErrorPolicy {
+        /// Stops on the first error.
+        #[default]
+        FailFast,
+        /// Keeps processing and yields all errors.
+        Continue,
+        /// Stops once the given number of errors is reached.
+        ///
+        /// `MaxErrors(0)` sets no limit and never stops, like [Continue](ErrorPolicy::Continue).
+        MaxErrors(usize),
+        /// Stops once the ratio of errors to processed items exceeds `ratio`.
+        /// The ratio is not checked until `min_items` items are processed.
+        ErrorRatio { ratio: f64, min_items: usize },
+    }
+
+    impl ErrorPolicy {
+        /// Returns `true` if the processing should stop after `num_errors` errors
+        /// out of `num_items` processed items.
+        pub fn should_stop(&self, num_items: usize, num_errors: usize) -> bool {
+            match *self {
+                Self::FailFast => num_errors > 0,
+                Self::Continue => false,
+                Self::MaxErrors(max) => max > 0 && num_errors >= max,
+                Self::ErrorRatio { ratio, min_items } => {
+                    num_items >= min_items && num_errors as f64 > num_items as f64 * ratio
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     common::*,
     config::{BufSize, ParParams},
-    rt,
+    rt::{self, RuntimeHandle},
     stream::StreamExt as _,
     try_stream::{TakeUntilError, TryStreamExt as _},
     utils,
@@ -15,6 +15,22 @@ pub type TryParUnfold<T, E> = TakeUntilError<RecvStream<'static, Result<T, E>>,
 /// Stream for the [try_par_unfold_blocking()] method.
 pub type TryParUnfoldBlocking<T, E> = TakeUntilError<RecvStream<'static, Result<T, E>>, T, E>;
 
+/// Stream for the [merge_sorted_by_key()] method.
+pub type MergeSortedByKey<T> = BoxStream<'static, Result<(usize, T), (usize, T)>>;
+
+/// Stream for the [join_by_key()] method.
+pub type JoinByKey<K, T> = BoxStream<'static, Result<(K, Vec<Option<T>>), (usize, T)>>;
+
+/// Stream for the [sync_by_key_approx()] method.
+pub type SyncByKeyApprox<K, T> = BoxStream<'static, Result<(K, Vec<Option<T>>), (usize, T)>>;
+
+/// Stream for the [sync_by_key_bounded()] method.
+pub type SyncByKeyBounded<T> = BoxStream<'static, Result<(usize, T), (usize, T)>>;
+
+/// Stream for the [try_sync_by_key_approx()] method.
+pub type TrySyncByKeyApprox<K, T, E> =
+    BoxStream<'static, Result<Result<(K, Vec<Option<T>>), (usize, T)>, E>>;
+
 // // par_unfold_builder
 
 // pub use par_unfold_builder::*;
@@ -63,6 +79,21 @@ mod iter_blocking {
     /// It is useful when consuming the iterator is computationally expensive and involves blocking code.
     /// It prevents blocking the asynchronous context when consuming the returned stream.
     pub fn iter_blocking<B, I>(buf_size: B, iter: I) -> RecvStream<'static, I::Item>
+    where
+        B: Into<BufSize>,
+        I: 'static + IntoIterator + Send,
+        I::Item: Send,
+    {
+        iter_blocking_on(None, buf_size, iter)
+    }
+
+    /// Consumes the iterator like [iter_blocking()] on the runtime if given, or on the default
+    /// runtime otherwise.
+    pub(crate) fn iter_blocking_on<B, I>(
+        runtime: Option<&RuntimeHandle>,
+        buf_size: B,
+        iter: I,
+    ) -> RecvStream<'static, I::Item>
     where
         B: Into<BufSize>,
         I: 'static + IntoIterator + Send,
@@ -71,7 +102,7 @@ mod iter_blocking {
         let buf_size = buf_size.into().get();
         let (tx, rx) = utils::channel(buf_size);
 
-        rt::spawn_blocking(move || {
+        rt::spawn_blocking_on(runtime, move || {
             for item in iter.into_iter() {
                 if tx.send(item).is_err() {
                     break;
@@ -142,20 +173,26 @@ mod par_unfold {
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
         let (output_tx, output_rx) = utils::channel(buf_size);
 
         (0..num_workers).for_each(|worker_index| {
             let output_tx = output_tx.clone();
             let state = init.clone();
             let f = f.clone();
+            let cancelled = cancel.cancelled();
 
-            rt::spawn(async move {
+            rt::spawn_on(runtime.as_ref(), async move {
                 let _ = stream::unfold((state, f), |(state, mut f)| async move {
                     f(worker_index, state)
                         .await
                         .map(|(item, state)| (item, (state, f)))
                 })
+                .take_until(cancelled)
                 .map(Ok)
                 .forward(output_tx.into_sink())
                 .await;
@@ -212,16 +249,25 @@ mod par_unfold {
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
         let (output_tx, output_rx) = utils::channel(buf_size);
 
         (0..num_workers).for_each(|worker_index| {
             let mut f = f.clone();
             let mut state = init.clone();
             let output_tx = output_tx.clone();
+            let cancel = cancel.clone();
 
-            rt::spawn_blocking(move || {
+            rt::spawn_blocking_on(runtime.as_ref(), move || {
                 while let Some((item, new_state)) = f(worker_index, state) {
+                    if cancel.is_cancelled() {
+                        break;
+                    }
+
                     if output_tx.send(item).is_ok() {
                         state = new_state;
                     } else {
@@ -339,16 +385,13 @@ mod sync {
                     threshold = min_items.iter().min().unwrap().clone();
 
                     // pop items below threshold
-                    if let Some(threshold) = &threshold {
-                        'output: while let Some(Reverse(KV { key, .. })) = heap.peek() {
-                            if key < threshold {
-                                let KV { value, index, .. } = heap.pop().unwrap().0;
-                                let ok = output_tx.send(Ok((index, value))).is_ok();
-                                if !ok {
-                                    break 'worker;
-                                }
-                            } else {
-                                break 'output;
+                    if let Some(threshold) = threshold.take() {
+                        while matches!(heap.peek(), Some(Reverse(KV { key, .. })) if *key < threshold)
+                        {
+                            let KV { value, index, .. } = heap.pop().unwrap().0;
+                            let ok = output_tx.send_async(Ok((index, value))).await.is_ok();
+                            if !ok {
+                                break 'worker;
                             }
                         }
                     }
@@ -356,7 +399,7 @@ mod sync {
 
                 // send remaining items
                 for Reverse(KV { index, value, .. }) in heap {
-                    let ok = output_tx.send(Ok((index, value))).is_ok();
+                    let ok = output_tx.send_async(Ok((index, value))).await.is_ok();
                     if !ok {
                         break 'worker;
                     }
@@ -370,6 +413,287 @@ mod sync {
     }
 }
 
+// sorted_heads
+
+use sorted_heads::*;
+
+mod sorted_heads {
+    use super::*;
+
+    /// Keeps the head item of each of the key-sorted streams.
+    pub(super) struct SortedHeads<K, T, F> {
+        streams: Vec<Option<BoxStream<'static, T>>>,
+        heads: Vec<Option<(K, T)>>,
+        prev_keys: Vec<Option<K>>,
+        key_fn: F,
+        strict: bool,
+    }
+
+    impl<K, T, F> SortedHeads<K, T, F>
+    where
+        F: Fn(&T) -> K,
+        K: Clone + Ord,
+    {
+        /// Creates the heads. If `strict` is set, an item with a key equal to the
+        /// previous item in the same stream is considered out of order as well.
+        pub(super) fn new<I, S>(streams: I, key_fn: F, strict: bool) -> Self
+        where
+            I: IntoIterator<Item = S>,
+            S: 'static + Stream<Item = T> + Send,
+        {
+            let streams: Vec<_> = streams
+                .into_iter()
+                .map(|stream| Some(stream.boxed()))
+                .collect();
+            let num_streams = streams.len();
+
+            Self {
+                streams,
+                heads: (0..num_streams).map(|_| None).collect(),
+                prev_keys: vec![None; num_streams],
+                key_fn,
+                strict,
+            }
+        }
+
+        /// Polls every unfinished stream without a head item.
+        ///
+        /// It is ready when each stream either has a head item or is finished. An out of
+        /// order item is returned as `Err((stream_index, item))` immediately.
+        pub(super) fn poll_fill(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), (usize, T)>> {
+            let mut is_pending = false;
+
+            for index in 0..self.streams.len() {
+                if self.heads[index].is_some() {
+                    continue;
+                }
+
+                let stream = match &mut self.streams[index] {
+                    Some(stream) => stream,
+                    None => continue,
+                };
+
+                match stream.poll_next_unpin(cx) {
+                    Ready(Some(item)) => {
+                        let key = (self.key_fn)(&item);
+
+                        match &self.prev_keys[index] {
+                            Some(prev) if key < *prev || (self.strict && key == *prev) => {
+                                return Ready(Err((index, item)));
+                            }
+                            _ => {
+                                self.heads[index] = Some((key, item));
+                            }
+                        }
+                    }
+                    Ready(None) => {
+                        self.streams[index] = None;
+                    }
+                    Pending => {
+                        is_pending = true;
+                    }
+                }
+            }
+
+            if is_pending {
+                Pending
+            } else {
+                Ready(Ok(()))
+            }
+        }
+
+        /// Returns the smallest key among head items and the smallest stream index having it.
+        pub(super) fn min(&self) -> Option<(&K, usize)> {
+            self.heads
+                .iter()
+                .enumerate()
+                .filter_map(|(index, head)| head.as_ref().map(|(key, _)| (key, index)))
+                .min()
+        }
+
+        /// Takes the head item of the stream if its key equals to `key`.
+        pub(super) fn take_if_eq(&mut self, index: usize, key: &K) -> Option<T> {
+            match &self.heads[index] {
+                Some((head_key, _)) if head_key == key => {
+                    let (key, item) = self.heads[index].take().unwrap();
+                    self.prev_keys[index] = Some(key);
+                    Some(item)
+                }
+                _ => None,
+            }
+        }
+
+        /// Returns true if the stream is finished and has no head item.
+        pub(super) fn is_exhausted(&self, index: usize) -> bool {
+            self.streams[index].is_none() && self.heads[index].is_none()
+        }
+
+        pub(super) fn num_streams(&self) -> usize {
+            self.streams.len()
+        }
+    }
+}
+
+// merge_sorted
+
+pub use merge_sorted::*;
+
+mod merge_sorted {
+    use super::*;
+
+    /// Merges streams sorted by the key of each item into a single sorted stream.
+    ///
+    /// The `key_fn` constructs the key for each item. Unlike [sync_by_key], the items
+    /// are not grouped by keys. The smallest item among the head items of all streams
+    /// is yielded in type `Ok((stream_index, item))` once every unfinished stream has
+    /// a head item. Items with equal keys are yielded in the order of stream indices.
+    ///
+    /// If any one of the `streams` generates an item with a key smaller than its
+    /// previous item, the item is yielded as `Err((stream_index, item))` immediately.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    ///
+    /// let stream1 = stream::iter([1, 4, 7]);
+    /// let stream2 = stream::iter([2, 5, 3]);
+    ///
+    /// let merged: Vec<_> = par_stream::merge_sorted_by_key([stream1, stream2], |&val| val)
+    ///     .collect()
+    ///     .await;
+    ///
+    /// assert_eq!(
+    ///     merged,
+    ///     [Ok((0, 1)), Ok((1, 2)), Ok((0, 4)), Ok((1, 5)), Err((1, 3)), Ok((0, 7))]
+    /// );
+    /// # })
+    /// ```
+    pub fn merge_sorted_by_key<I, F, K, S>(streams: I, key_fn: F) -> MergeSortedByKey<S::Item>
+    where
+        I: IntoIterator<Item = S>,
+        S: 'static + Stream + Send,
+        S::Item: 'static + Send,
+        F: 'static + Fn(&S::Item) -> K + Send,
+        K: 'static + Clone + Ord + Send,
+    {
+        let mut heads = SortedHeads::new(streams, key_fn, false);
+
+        stream::poll_fn(move |cx| {
+            if let Err(err) = ready!(heads.poll_fill(cx)) {
+                return Ready(Some(Err(err)));
+            }
+
+            // yield the smallest head item, preferring the smaller stream index on ties
+            let (key, index) = match heads.min() {
+                Some((key, index)) => (key.clone(), index),
+                None => return Ready(None),
+            };
+            let item = heads.take_if_eq(index, &key).unwrap();
+            Ready(Some(Ok((index, item))))
+        })
+        .boxed()
+    }
+}
+
+// join
+
+pub use join::*;
+
+mod join {
+    use super::*;
+
+    /// The kind of join for [join_by_key()].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum JoinKind {
+        /// Yields the keys present in all streams.
+        Inner,
+        /// Yields the keys present in the first stream.
+        Left,
+        /// Yields the keys present in any one of the streams.
+        Outer,
+    }
+
+    /// Joins streams sorted by the key of each item into frames of items sharing a key.
+    ///
+    /// The `key_fn` constructs the key for each item. Each stream is expected to be
+    /// strictly increasing in keys. For each key, a frame `Ok((key, items))` is
+    /// yielded where `items[stream_index]` is the item of that stream having the key,
+    /// or `None` if the stream does not have it. The `kind` decides which frames are
+    /// yielded according to [JoinKind].
+    ///
+    /// If any one of the `streams` generates an item with a key not greater than its
+    /// previous item, the item is yielded as `Err((stream_index, item))` immediately.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::JoinKind;
+    ///
+    /// let frames: Vec<_> = par_stream::join_by_key(
+    ///     JoinKind::Left,
+    ///     [stream::iter([1, 2, 4]), stream::iter([2, 3, 4])],
+    ///     |&val| val,
+    /// )
+    /// .collect()
+    /// .await;
+    ///
+    /// assert_eq!(
+    ///     frames,
+    ///     [
+    ///         Ok((1, vec![Some(1), None])),
+    ///         Ok((2, vec![Some(2), Some(2)])),
+    ///         Ok((4, vec![Some(4), Some(4)])),
+    ///     ]
+    /// );
+    /// # })
+    /// ```
+    pub fn join_by_key<I, F, K, S>(kind: JoinKind, streams: I, key_fn: F) -> JoinByKey<K, S::Item>
+    where
+        I: IntoIterator<Item = S>,
+        S: 'static + Stream + Send,
+        S::Item: 'static + Send,
+        F: 'static + Fn(&S::Item) -> K + Send,
+        K: 'static + Clone + Ord + Send,
+    {
+        let mut heads = SortedHeads::new(streams, key_fn, true);
+
+        stream::poll_fn(move |cx| loop {
+            if let Err(err) = ready!(heads.poll_fill(cx)) {
+                return Ready(Some(Err(err)));
+            }
+
+            // no more frames can be yielded once a required stream is exhausted
+            let num_streams = heads.num_streams();
+            let is_done = match kind {
+                JoinKind::Inner => (0..num_streams).any(|index| heads.is_exhausted(index)),
+                JoinKind::Left => num_streams > 0 && heads.is_exhausted(0),
+                JoinKind::Outer => false,
+            };
+            if is_done {
+                return Ready(None);
+            }
+
+            let key = match heads.min() {
+                Some((key, _)) => key.clone(),
+                None => return Ready(None),
+            };
+            let items: Vec<_> = (0..num_streams)
+                .map(|index| heads.take_if_eq(index, &key))
+                .collect();
+
+            let is_selected = match kind {
+                JoinKind::Inner => items.iter().all(Option::is_some),
+                JoinKind::Left => items[0].is_some(),
+                JoinKind::Outer => true,
+            };
+            if is_selected {
+                return Ready(Some(Ok((key, items))));
+            }
+        })
+        .boxed()
+    }
+}
+
 // try_sync
 
 pub use try_sync::*;
@@ -474,7 +798,7 @@ mod try_sync {
                                 *prev = key.clone();
                             }
                             Some(_) => {
-                                let ok = output_tx.send(Ok(Err((index, item)))).is_ok();
+                                let ok = output_tx.send_async(Ok(Err((index, item)))).await.is_ok();
                                 if !ok {
                                     break 'worker;
                                 }
@@ -495,16 +819,13 @@ mod try_sync {
                     threshold = min_items.iter().min().unwrap().clone();
 
                     // pop items below threshold
-                    if let Some(threshold) = &threshold {
-                        'output: while let Some(Reverse(KV { key, .. })) = heap.peek() {
-                            if key < threshold {
-                                let KV { value, index, .. } = heap.pop().unwrap().0;
-                                let ok = output_tx.send(Ok(Ok((index, value)))).is_ok();
-                                if !ok {
-                                    break 'worker;
-                                }
-                            } else {
-                                break 'output;
+                    if let Some(threshold) = threshold.take() {
+                        while matches!(heap.peek(), Some(Reverse(KV { key, .. })) if *key < threshold)
+                        {
+                            let KV { value, index, .. } = heap.pop().unwrap().0;
+                            let ok = output_tx.send_async(Ok(Ok((index, value)))).await.is_ok();
+                            if !ok {
+                                break 'worker;
                             }
                         }
                     }
@@ -512,7 +833,7 @@ mod try_sync {
 
                 // send remaining items
                 for Reverse(KV { index, value, .. }) in heap {
-                    let ok = output_tx.send(Ok(Ok((index, value)))).is_ok();
+                    let ok = output_tx.send_async(Ok(Ok((index, value)))).await.is_ok();
                     if !ok {
                         break 'worker;
                     }
@@ -526,6 +847,556 @@ mod try_sync {
     }
 }
 
+// sync_approx
+
+pub use sync_approx::*;
+
+mod sync_approx {
+    use super::*;
+    use std::{convert::Infallible, ops::Sub};
+
+    type Frame<K, T> = Result<(K, Vec<Option<T>>), (usize, T)>;
+
+    /// The policy on the items not synchronized into complete frames by
+    /// [sync_by_key_approx()] and [try_sync_by_key_approx()].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum UnmatchedPolicy {
+        /// Yields complete frames only and discards the other items.
+        Drop,
+        /// Yields incomplete frames with `None` for the streams without a match,
+        /// and discards the items matching no item of the first stream.
+        Partial,
+        /// Yields complete frames only. The other items are yielded as `Err((stream_index, item))`.
+        Yield,
+    }
+
+    /// Synchronize streams by matching each item of the first stream to the items of
+    /// the other streams with the nearest keys within the `tolerance`.
+    ///
+    /// The `key_fn` constructs the key for each item. The first stream is the reference.
+    /// For each reference item, the item with the nearest key within `tolerance` is taken
+    /// from each other stream, and a frame `Ok((key, items))` is yielded where `key` is
+    /// the key of the reference item and `items[stream_index]` is the matched item.
+    /// Each item is matched at most once. The `policy` decides whether the incomplete
+    /// frames and the unmatched items are yielded.
+    ///
+    /// If any one of the `streams` generates a non-monotonic item. The item is
+    /// yielded as `Err((stream_index, item))` immediately.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::UnmatchedPolicy;
+    ///
+    /// let camera = stream::iter(vec![100u64, 200, 300]);
+    /// let lidar = stream::iter(vec![103u64, 198, 260, 304]);
+    ///
+    /// let frames: Vec<_> =
+    ///     par_stream::sync_by_key_approx(5, UnmatchedPolicy::Drop, |&ts| ts, [camera, lidar])
+    ///         .collect()
+    ///         .await;
+    ///
+    /// assert_eq!(
+    ///     frames,
+    ///     [
+    ///         Ok((100, vec![Some(100), Some(103)])),
+    ///         Ok((200, vec![Some(200), Some(198)])),
+    ///         Ok((300, vec![Some(300), Some(304)])),
+    ///     ]
+    /// );
+    /// # })
+    /// ```
+    pub fn sync_by_key_approx<I, F, K, D, S>(
+        tolerance: D,
+        policy: UnmatchedPolicy,
+        key_fn: F,
+        streams: I,
+    ) -> SyncByKeyApprox<K, S::Item>
+    where
+        I: IntoIterator<Item = S>,
+        S: 'static + Stream + Send,
+        S::Item: 'static + Send,
+        F: 'static + Fn(&S::Item) -> K + Send,
+        K: 'static + Clone + Ord + Send + Sub<Output = D>,
+        D: 'static + Ord + Send,
+    {
+        let streams = streams
+            .into_iter()
+            .map(|stream| stream.map(Ok::<_, Infallible>).boxed())
+            .collect();
+
+        sync_approx(tolerance, policy, key_fn, streams)
+            .map(|result| match result {
+                Ok(frame) => frame,
+                Err(never) => match never {},
+            })
+            .boxed()
+    }
+
+    /// Synchronize streams by matching keys within the `tolerance`. It is fallible counterpart of [sync_by_key_approx].
+    ///
+    /// The frames and the unmatched items are yielded in type `Ok(Ok((key, items)))`
+    /// and `Ok(Err((stream_index, item)))` respectively.
+    ///
+    /// When an error is received from one of the `streams`. The returned stream
+    /// yields `Err(err)` and no longer produce future items.
+    pub fn try_sync_by_key_approx<I, F, K, D, T, E, S>(
+        tolerance: D,
+        policy: UnmatchedPolicy,
+        key_fn: F,
+        streams: I,
+    ) -> TrySyncByKeyApprox<K, T, E>
+    where
+        I: IntoIterator<Item = S>,
+        S: 'static + Stream<Item = Result<T, E>> + Send,
+        T: 'static + Send,
+        E: 'static + Send,
+        F: 'static + Fn(&T) -> K + Send,
+        K: 'static + Clone + Ord + Send + Sub<Output = D>,
+        D: 'static + Ord + Send,
+    {
+        let streams = streams.into_iter().map(|stream| stream.boxed()).collect();
+        sync_approx(tolerance, policy, key_fn, streams)
+    }
+
+    fn sync_approx<F, K, D, T, E>(
+        tolerance: D,
+        policy: UnmatchedPolicy,
+        key_fn: F,
+        streams: Vec<BoxStream<'static, Result<T, E>>>,
+    ) -> BoxStream<'static, Result<Frame<K, T>, E>>
+    where
+        T: 'static + Send,
+        E: 'static + Send,
+        F: 'static + Fn(&T) -> K + Send,
+        K: 'static + Clone + Ord + Send + Sub<Output = D>,
+        D: 'static + Ord + Send,
+    {
+        let distance = |lhs: &K, rhs: &K| -> D {
+            if lhs >= rhs {
+                lhs.clone() - rhs.clone()
+            } else {
+                rhs.clone() - lhs.clone()
+            }
+        };
+
+        let num_streams = streams.len();
+        let mut streams: Vec<_> = streams.into_iter().map(Some).collect();
+        let mut buffers: Vec<VecDeque<(K, T)>> =
+            (0..num_streams).map(|_| VecDeque::new()).collect();
+        let mut prev_keys: Vec<Option<K>> = vec![None; num_streams];
+        let mut outputs: VecDeque<Result<Frame<K, T>, E>> = VecDeque::new();
+        let mut is_terminated = num_streams == 0;
+
+        stream::poll_fn(move |cx| loop {
+            if let Some(output) = outputs.pop_front() {
+                return Ready(Some(output));
+            }
+            if is_terminated {
+                return Ready(None);
+            }
+
+            let is_reference_exhausted = streams[0].is_none() && buffers[0].is_empty();
+
+            if is_reference_exhausted {
+                // the remaining items cannot be matched anymore
+                if policy != UnmatchedPolicy::Yield {
+                    is_terminated = true;
+                    continue;
+                }
+
+                for (index, buffer) in buffers.iter_mut().enumerate() {
+                    outputs.extend(buffer.drain(..).map(|(_, item)| Ok(Err((index, item)))));
+                }
+            } else if let Some((ref_key, _)) = buffers[0].front() {
+                // the nearest items are known once the other streams reach the reference key
+                let is_decidable = (1..num_streams).all(|index| {
+                    streams[index].is_none()
+                        || matches!(buffers[index].back(), Some((key, _)) if key >= ref_key)
+                });
+
+                if is_decidable {
+                    let (ref_key, ref_item) = buffers[0].pop_front().unwrap();
+                    let mut items = vec![Some(ref_item)];
+
+                    for (index, buffer) in buffers.iter_mut().enumerate().skip(1) {
+                        // evict the items too early for this and later reference items
+                        while let Some((key, _)) = buffer.front() {
+                            if *key >= ref_key || distance(key, &ref_key) <= tolerance {
+                                break;
+                            }
+
+                            let (_, item) = buffer.pop_front().unwrap();
+                            if policy == UnmatchedPolicy::Yield {
+                                outputs.push_back(Ok(Err((index, item))));
+                            }
+                        }
+
+                        let nearest = buffer
+                            .iter()
+                            .map(|(key, _)| distance(key, &ref_key))
+                            .enumerate()
+                            .take_while(|(_, dist)| *dist <= tolerance)
+                            .min_by(|(_, lhs), (_, rhs)| lhs.cmp(rhs))
+                            .map(|(pos, _)| pos);
+                        items.push(nearest.map(|pos| buffer.remove(pos).unwrap().1));
+                    }
+
+                    let is_complete = items.iter().all(Option::is_some);
+
+                    match policy {
+                        _ if is_complete => outputs.push_back(Ok(Ok((ref_key, items)))),
+                        UnmatchedPolicy::Partial => outputs.push_back(Ok(Ok((ref_key, items)))),
+                        UnmatchedPolicy::Drop => {}
+                        UnmatchedPolicy::Yield => {
+                            let unmatched = items
+                                .into_iter()
+                                .enumerate()
+                                .filter_map(|(index, item)| Some(Ok(Err((index, item?)))));
+                            outputs.extend(unmatched);
+                        }
+                    }
+
+                    continue;
+                }
+            }
+
+            // poll the streams whose items are needed to make progress
+            let ref_key = buffers[0].front().map(|(key, _)| key.clone());
+            let needs_poll: Vec<bool> = (0..num_streams)
+                .map(|index| {
+                    if streams[index].is_none() {
+                        false
+                    } else if index == 0 {
+                        buffers[0].is_empty()
+                    } else if is_reference_exhausted {
+                        true
+                    } else {
+                        match (&ref_key, buffers[index].back()) {
+                            (Some(ref_key), Some((key, _))) => key < ref_key,
+                            (Some(_), None) => true,
+                            (None, _) => false,
+                        }
+                    }
+                })
+                .collect();
+
+            if !needs_poll.iter().any(|&needs_poll| needs_poll) {
+                is_terminated = true;
+                continue;
+            }
+
+            let mut is_progressed = false;
+
+            for index in 0..num_streams {
+                if !needs_poll[index] {
+                    continue;
+                }
+
+                match streams[index].as_mut().unwrap().poll_next_unpin(cx) {
+                    Ready(Some(Ok(item))) => {
+                        let key = key_fn(&item);
+
+                        match &prev_keys[index] {
+                            Some(prev) if key < *prev => {
+                                outputs.push_back(Ok(Err((index, item))));
+                            }
+                            _ => {
+                                prev_keys[index] = Some(key.clone());
+                                buffers[index].push_back((key, item));
+                            }
+                        }
+                        is_progressed = true;
+                    }
+                    Ready(Some(Err(err))) => {
+                        outputs.push_back(Err(err));
+                        is_terminated = true;
+                        is_progressed = true;
+                        break;
+                    }
+                    Ready(None) => {
+                        streams[index] = None;
+                        is_progressed = true;
+                    }
+                    Pending => {}
+                }
+            }
+
+            if !is_progressed {
+                return Pending;
+            }
+        })
+        .boxed()
+    }
+}
+
+// sync_bounded
+
+pub use sync_bounded::*;
+
+mod sync_bounded {
+    use super::*;
+    use std::{cmp::Reverse, collections::BinaryHeap, time::Instant};
+
+    #[derive(Derivative)]
+    #[derivative(PartialEq, Eq, PartialOrd, Ord)]
+    struct KV<K, V> {
+        pub key: K,
+        pub index: usize,
+        #[derivative(PartialEq = "ignore", PartialOrd = "ignore", Ord = "ignore")]
+        pub value: V,
+    }
+
+    /// The input item of [sync_by_key_bounded()].
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub enum SyncInput<K, T> {
+        /// A stream item.
+        Item(T),
+        /// A promise that the stream generates no more items with keys below the watermark.
+        Watermark(K),
+    }
+
+    /// The policy on late items for [sync_by_key_bounded()].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub enum LatePolicy {
+        /// Discards late items.
+        Drop,
+        /// Yields late items as `Err((stream_index, item))`.
+        #[default]
+        Error,
+        /// Yields late items as `Ok((stream_index, item))` regardless of the order.
+        Emit,
+    }
+
+    /// The options for [sync_by_key_bounded()].
+    ///
+    /// ```rust
+    /// use par_stream::{LatePolicy, SyncOptions};
+    /// use std::time::Duration;
+    ///
+    /// let options = SyncOptions::new()
+    ///     .idle_timeout(Duration::from_millis(100))
+    ///     .max_buffered(1024)
+    ///     .late_policy(LatePolicy::Drop);
+    /// ```
+    #[derive(Debug, Clone, Default)]
+    pub struct SyncOptions {
+        /// The size of the output buffer. It defaults to the number of CPUs.
+        pub buf_size: Option<usize>,
+        /// The duration after which a stream without new inputs is treated as advanced.
+        pub idle_timeout: Option<Duration>,
+        /// The maximum number of buffered items. The smallest items are yielded once exceeded.
+        pub max_buffered: Option<usize>,
+        /// The policy on late items.
+        pub late_policy: LatePolicy,
+    }
+
+    impl SyncOptions {
+        /// Creates the options without idle timeout and buffer limit.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Sets the size of the output buffer.
+        pub fn buf_size(mut self, buf_size: usize) -> Self {
+            self.buf_size = Some(buf_size);
+            self
+        }
+
+        /// Sets the idle timeout.
+        pub fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+            self.idle_timeout = Some(idle_timeout);
+            self
+        }
+
+        /// Sets the maximum number of buffered items.
+        pub fn max_buffered(mut self, max_buffered: usize) -> Self {
+            self.max_buffered = Some(max_buffered);
+            self
+        }
+
+        /// Sets the policy on late items.
+        pub fn late_policy(mut self, late_policy: LatePolicy) -> Self {
+            self.late_policy = late_policy;
+            self
+        }
+    }
+
+    /// Synchronize streams by pairing up keys of each stream item with bounded memory.
+    ///
+    /// It works like [sync_by_key], except that a stream is treated as advanced
+    /// beyond a key once it generates a [SyncInput::Watermark] with that key, once
+    /// it generates no inputs within the `idle_timeout` in `options`, or once it
+    /// finishes. The idle stream becomes active again on its next input. When the
+    /// number of buffered items exceeds `max_buffered`, the smallest items are
+    /// yielded without waiting for other streams.
+    ///
+    /// An item is late if its key is smaller than the previous key or watermark of
+    /// its stream, or smaller than an already yielded item. Late items are handled
+    /// according to the [LatePolicy] in `options`.
+    pub fn sync_by_key_bounded<I, F, K, T, S>(
+        options: SyncOptions,
+        key_fn: F,
+        streams: I,
+    ) -> SyncByKeyBounded<T>
+    where
+        I: IntoIterator<Item = S>,
+        S: 'static + Stream<Item = SyncInput<K, T>> + Send,
+        T: 'static + Send,
+        F: 'static + Fn(&T) -> K + Send,
+        K: 'static + Clone + Ord + Send,
+    {
+        let SyncOptions {
+            buf_size,
+            idle_timeout,
+            max_buffered,
+            late_policy,
+        } = options;
+        let buf_size = buf_size.unwrap_or_else(num_cpus::get);
+
+        // mark the end of each stream by a None
+        let streams: Vec<_> = streams
+            .into_iter()
+            .enumerate()
+            .map(|(stream_index, stream)| {
+                stream
+                    .map(move |input| (stream_index, Some(input)))
+                    .chain(stream::once(future::ready((stream_index, None))))
+                    .boxed()
+            })
+            .collect();
+        let num_streams = streams.len();
+
+        let mut input_stream = stream::select_all(streams);
+        let (output_tx, output_rx) = utils::channel(buf_size);
+
+        rt::spawn(async move {
+            let mut heap: BinaryHeap<Reverse<KV<K, T>>> = BinaryHeap::new();
+            // the key below which no more items are expected from each stream
+            let mut frontiers: Vec<Option<K>> = vec![None; num_streams];
+            let mut last_active = vec![Instant::now(); num_streams];
+            let mut is_idle = vec![false; num_streams];
+            let mut is_finished = vec![false; num_streams];
+            let mut yielded_key: Option<K> = None;
+
+            'worker: loop {
+                // wait for the next input until the earliest idle deadline
+                let deadline = idle_timeout.and_then(|idle_timeout| {
+                    (0..num_streams)
+                        .filter(|&index| !is_idle[index] && !is_finished[index])
+                        .map(|index| last_active[index] + idle_timeout)
+                        .min()
+                });
+                let input = match deadline {
+                    Some(deadline) => {
+                        let duration = deadline.saturating_duration_since(Instant::now());
+                        rt::timeout(duration, input_stream.next()).await.ok()
+                    }
+                    None => Some(input_stream.next().await),
+                };
+
+                match input {
+                    None => {
+                        // mark streams idle after timeout
+                        let now = Instant::now();
+                        let idle_timeout = idle_timeout.unwrap();
+
+                        for index in 0..num_streams {
+                            if !is_finished[index] && now >= last_active[index] + idle_timeout {
+                                is_idle[index] = true;
+                            }
+                        }
+                    }
+                    Some(None) => break,
+                    Some(Some((index, None))) => {
+                        is_finished[index] = true;
+                    }
+                    Some(Some((index, Some(input)))) => {
+                        last_active[index] = Instant::now();
+                        is_idle[index] = false;
+
+                        match input {
+                            SyncInput::Watermark(key) => {
+                                let frontier = &mut frontiers[index];
+                                if !matches!(frontier, Some(frontier) if *frontier >= key) {
+                                    *frontier = Some(key);
+                                }
+                            }
+                            SyncInput::Item(item) => {
+                                let key = key_fn(&item);
+                                let is_late = matches!(&frontiers[index], Some(frontier) if key < *frontier)
+                                    || matches!(&yielded_key, Some(yielded) if key < *yielded);
+
+                                if is_late {
+                                    let output = match late_policy {
+                                        LatePolicy::Drop => continue 'worker,
+                                        LatePolicy::Error => Err((index, item)),
+                                        LatePolicy::Emit => Ok((index, item)),
+                                    };
+                                    if output_tx.send_async(output).await.is_err() {
+                                        break 'worker;
+                                    }
+                                    continue 'worker;
+                                }
+
+                                frontiers[index] = Some(key.clone());
+                                heap.push(Reverse(KV {
+                                    index,
+                                    key,
+                                    value: item,
+                                }));
+                            }
+                        }
+                    }
+                }
+
+                // the smallest frontier among active streams, or None if some active
+                // stream has no frontier yet
+                let threshold: Option<Option<K>> = (0..num_streams)
+                    .filter(|&index| !is_idle[index] && !is_finished[index])
+                    .map(|index| frontiers[index].as_ref())
+                    .try_fold(None, |min: Option<&K>, frontier| {
+                        let frontier = frontier?;
+                        Some(Some(match min {
+                            Some(min) if min <= frontier => min,
+                            _ => frontier,
+                        }))
+                    })
+                    .map(|min| min.cloned());
+
+                // pop items below threshold or beyond the buffer limit
+                loop {
+                    let is_ready = match heap.peek() {
+                        Some(Reverse(KV { key, .. })) => {
+                            let is_below = match &threshold {
+                                Some(Some(threshold)) => key < threshold,
+                                Some(None) => true,
+                                None => false,
+                            };
+                            let is_full = matches!(max_buffered, Some(max_buffered) if heap.len() > max_buffered);
+                            is_below || is_full
+                        }
+                        None => false,
+                    };
+
+                    if !is_ready {
+                        break;
+                    }
+
+                    let KV { key, index, value } = heap.pop().unwrap().0;
+                    yielded_key = Some(key);
+
+                    if output_tx.send_async(Ok((index, value))).await.is_err() {
+                        break 'worker;
+                    }
+                }
+            }
+        });
+
+        output_rx.into_stream().boxed()
+    }
+}
+
 // try_par_unfold
 
 pub use try_par_unfold::*;
@@ -561,7 +1432,11 @@ mod try_par_unfold {
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
         let (output_tx, output_rx) = utils::channel(buf_size);
         let (terminate_tx, _) = broadcast::channel::<()>(1);
 
@@ -571,12 +1446,14 @@ mod try_par_unfold {
             let output_tx = output_tx.clone();
             let mut terminate_rx = terminate_tx.subscribe();
             let terminate_tx = terminate_tx.clone();
+            let cancelled = cancel.cancelled();
 
-            rt::spawn(async move {
+            rt::spawn_on(runtime.as_ref(), async move {
                 let _ = stream::repeat(())
                     .take_until(async move {
                         let _ = terminate_rx.recv().await;
                     })
+                    .take_until(cancelled)
                     .map(Ok)
                     .try_stateful_then(
                         (f, terminate_tx, state),
@@ -636,7 +1513,11 @@ mod try_par_unfold_blocking {
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
         let (output_tx, output_rx) = utils::channel(buf_size);
         let terminate = Arc::new(AtomicBool::new(false));
 
@@ -645,9 +1526,10 @@ mod try_par_unfold_blocking {
             let mut state = init.clone();
             let output_tx = output_tx.clone();
             let terminate = terminate.clone();
+            let cancel = cancel.clone();
 
-            rt::spawn_blocking(move || loop {
-                if terminate.load(Acquire) {
+            rt::spawn_blocking_on(runtime.as_ref(), move || loop {
+                if terminate.load(Acquire) || cancel.is_cancelled() {
                     break;
                 }
 
@@ -729,6 +1611,196 @@ mod tests {
         }
 
 
+        async fn merge_sorted_by_key_test() {
+            let mut rng = rand::thread_rng();
+            let inputs: Vec<Vec<u32>> = (0..4)
+                .map(|_| {
+                    let len = rng.gen_range(0..100);
+                    let mut vec: Vec<_> = (0..len).map(|_| rng.gen_range(0..50)).collect();
+                    vec.sort_unstable();
+                    vec
+                })
+                .collect();
+
+            let streams = inputs.iter().cloned().map(|vec| {
+                stream::iter(vec).then(|val| async move {
+                    rt::sleep(Duration::from_micros(val as u64 % 3)).await;
+                    val
+                })
+            });
+            let merged: Vec<_> = super::merge_sorted_by_key(streams, |&val| val)
+                .map(|result| result.unwrap())
+                .collect()
+                .await;
+
+            let mut expect: Vec<_> = inputs
+                .iter()
+                .enumerate()
+                .flat_map(|(index, vec)| vec.iter().map(move |&val| (index, val)))
+                .collect();
+            expect.sort_by_key(|&(index, val)| (val, index));
+
+            assert_eq!(merged, expect);
+        }
+
+
+        async fn join_by_key_test() {
+            let inputs = || {
+                [
+                    stream::iter(vec![1, 2, 4, 6]),
+                    stream::iter(vec![2, 3, 4, 5, 6]),
+                    stream::iter(vec![0, 2, 6, 5]),
+                ]
+            };
+
+            let inner: Vec<_> = super::join_by_key(JoinKind::Inner, inputs(), |&val| val)
+                .collect()
+                .await;
+            assert_eq!(
+                inner,
+                [
+                    Ok((2, vec![Some(2), Some(2), Some(2)])),
+                    Ok((6, vec![Some(6), Some(6), Some(6)])),
+                    Err((2, 5)),
+                ]
+            );
+
+            let outer: Vec<_> = super::join_by_key(JoinKind::Outer, inputs(), |&val| val)
+                .filter_map(|result| future::ready(result.ok()))
+                .map(|(key, items)| (key, items.iter().map(Option::is_some).collect::<Vec<_>>()))
+                .collect()
+                .await;
+            assert_eq!(
+                outer,
+                [
+                    (0, vec![false, false, true]),
+                    (1, vec![true, false, false]),
+                    (2, vec![true, true, true]),
+                    (3, vec![false, true, false]),
+                    (4, vec![true, true, false]),
+                    (5, vec![false, true, false]),
+                    (6, vec![true, true, true]),
+                ]
+            );
+        }
+
+
+        async fn sync_by_key_approx_test() {
+            let inputs = || {
+                [
+                    stream::iter(vec![10u32, 20, 30, 40]),
+                    stream::iter(vec![1u32, 11, 19, 21, 42]),
+                    stream::iter(vec![9u32, 31, 29]),
+                ]
+            };
+
+            let yielded: Vec<_> =
+                super::sync_by_key_approx(2, UnmatchedPolicy::Yield, |&val| val, inputs())
+                    .collect()
+                    .await;
+            assert_eq!(
+                yielded,
+                [
+                    Err((1, 1)),
+                    Ok((10, vec![Some(10), Some(11), Some(9)])),
+                    Err((0, 20)),
+                    Err((1, 19)),
+                    Err((1, 21)),
+                    Err((0, 30)),
+                    Err((2, 31)),
+                    Err((2, 29)),
+                    Err((0, 40)),
+                    Err((1, 42)),
+                ]
+            );
+
+            let partial: Vec<_> =
+                super::sync_by_key_approx(2, UnmatchedPolicy::Partial, |&val| val, inputs())
+                    .collect()
+                    .await;
+            assert_eq!(
+                partial,
+                [
+                    Ok((10, vec![Some(10), Some(11), Some(9)])),
+                    Ok((20, vec![Some(20), Some(19), None])),
+                    Ok((30, vec![Some(30), None, Some(31)])),
+                    Err((2, 29)),
+                    Ok((40, vec![Some(40), Some(42), None])),
+                ]
+            );
+
+            let results: Vec<_> = super::try_sync_by_key_approx(
+                2,
+                UnmatchedPolicy::Drop,
+                |&val| val,
+                [
+                    stream::iter(vec![Ok(10u32), Ok(20), Ok(30)]),
+                    stream::iter(vec![Ok(11u32), Err("error"), Ok(30)]),
+                ],
+            )
+            .collect()
+            .await;
+            assert_eq!(results, [Ok(Ok((10, vec![Some(10), Some(11)]))), Err("error")]);
+        }
+
+
+        async fn sync_by_key_bounded_test() {
+            // the silent stream is treated as advanced after the idle timeout
+            {
+                let active = stream::iter([1, 3, 2, 4]).map(SyncInput::Item).boxed();
+                let silent = stream::pending().boxed();
+                let options = SyncOptions::new()
+                    .idle_timeout(Duration::from_millis(50))
+                    .late_policy(LatePolicy::Drop);
+
+                let collected: Vec<_> =
+                    super::sync_by_key_bounded(options, |&val: &i32| val, [active, silent])
+                        .take(3)
+                        .collect()
+                        .await;
+                assert_eq!(collected, [Ok((0, 1)), Ok((0, 3)), Ok((0, 4))]);
+            }
+
+            // the smallest items are yielded once the buffer is full
+            {
+                let active = stream::iter(1..=4).map(SyncInput::Item).boxed();
+                let silent = stream::pending().boxed();
+                let options = SyncOptions::new().max_buffered(2);
+
+                let collected: Vec<_> =
+                    super::sync_by_key_bounded(options, |&val: &i32| val, [active, silent])
+                        .take(2)
+                        .collect()
+                        .await;
+                assert_eq!(collected, [Ok((0, 1)), Ok((0, 2))]);
+            }
+
+            // the watermark advances a stream and the items below it are late
+            {
+                let stream1 = stream::iter([1, 5, 12]).map(SyncInput::Item).boxed();
+                let stream2 = stream::iter([
+                    SyncInput::Watermark(10),
+                    SyncInput::Item(3),
+                    SyncInput::Item(11),
+                ]).boxed();
+
+                let (synced, late): (Vec<_>, Vec<_>) =
+                    super::sync_by_key_bounded(SyncOptions::new(), |&val| val, [stream1, stream2])
+                        .map(|result| match result {
+                            Ok(item) => (Some(item), None),
+                            Err(item) => (None, Some(item)),
+                        })
+                        .unzip()
+                        .await;
+                let synced: Vec<_> = synced.into_iter().flatten().collect();
+                let late: Vec<_> = late.into_iter().flatten().collect();
+
+                assert_eq!(synced, [(0, 1), (0, 5), (1, 11), (0, 12)]);
+                assert_eq!(late, [(1, 3)]);
+            }
+        }
+
+
         async fn par_unfold_test() {
             let max_quota = 100;
 
@@ -1,4 +1,5 @@
 use crate::common::*;
+use std::sync::Mutex;
 
 /// The trait extends [Stream](futures::stream::Stream) types with ordering manipulation combinators.
 pub trait IndexStreamExt
@@ -13,7 +14,9 @@ where
     /// unordered tasks.
     ///
     /// The index numbers must start from zero, be unique and contiguous. Index not starting
-    /// from zero causes the stream to hang indefinitely.
+    /// from zero causes the stream to hang indefinitely. Use
+    /// [reorder_enumerated_checked()](IndexStreamExt::reorder_enumerated_checked) to report
+    /// these cases as errors.
     ///
     /// # Panics
     /// The repeating of an index will cause the stream to panic.
@@ -45,6 +48,95 @@ where
     /// # })
     /// ```
     fn reorder_enumerated(self) -> ReorderEnumerated<Self, Self::IndexedItem>;
+
+    /// Reorders the input items `(index, item)` like [reorder_enumerated()](IndexStreamExt::reorder_enumerated),
+    /// but buffers the items in a ring buffer offset from the next index to be yielded.
+    ///
+    /// It avoids hashing on every item and is faster when the index numbers arrive close
+    /// to the next index to be yielded, which is the usual case of parallel unordered tasks
+    /// on an enumerated stream. The buffer grows with the distance from the next index
+    /// rather than the number of pending items, so sparse far-ahead indices waste memory.
+    ///
+    /// # Panics
+    /// The repeating of an index will cause the stream to panic.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let values: Vec<_> = stream::iter([(2, 'c'), (0, 'a'), (3, 'd'), (1, 'b')])
+    ///     .reorder_enumerated_dense()
+    ///     .collect()
+    ///     .await;
+    /// assert_eq!(values, ['a', 'b', 'c', 'd']);
+    /// # })
+    /// ```
+    fn reorder_enumerated_dense(self) -> ReorderEnumeratedDense<Self, Self::IndexedItem>;
+
+    /// Reorders the input items `(index, item)` like [reorder_enumerated()](IndexStreamExt::reorder_enumerated),
+    /// but reports malformed index numbers as [ReorderError] instead of panicking or hanging.
+    ///
+    /// The index numbers are expected to start from `start`. The stream yields an `Err` and
+    /// terminates if an index repeats, if an index is less than `start`, or if the input
+    /// ends while some items are still waiting for a missing index.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::{prelude::*, ReorderError};
+    ///
+    /// let values: Vec<_> = stream::iter([(11, 'b'), (10, 'a'), (13, 'd')])
+    ///     .reorder_enumerated_checked(10)
+    ///     .collect()
+    ///     .await;
+    /// assert_eq!(
+    ///     values,
+    ///     [
+    ///         Ok('a'),
+    ///         Ok('b'),
+    ///         Err(ReorderError::Gap {
+    ///             missing: 12,
+    ///             num_buffered: 1
+    ///         })
+    ///     ]
+    /// );
+    /// # })
+    /// ```
+    fn reorder_enumerated_checked(
+        self,
+        start: usize,
+    ) -> ReorderEnumeratedChecked<Self, Self::IndexedItem>;
+
+    /// Reorders the input items `(index, item)` within each key computed by `key_fn`
+    /// and returns `item`.
+    ///
+    /// The index numbers count the items of each key separately. They must start from zero,
+    /// be unique and contiguous within a key. Items of the same key are produced in index order,
+    /// while an item of one key never waits for items of other keys.
+    ///
+    /// The stream keeps the next index number for every key seen so far, so that its memory
+    /// grows with the number of distinct keys.
+    ///
+    /// # Panics
+    /// The repeating of an index within a key will cause the stream to panic.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let values: Vec<_> = stream::iter([(1, ('a', 1)), (0, ('b', 0)), (0, ('a', 0))])
+    ///     .reorder_by_key(|&(key, _)| key)
+    ///     .collect()
+    ///     .await;
+    /// assert_eq!(values, [('b', 0), ('a', 0), ('a', 1)]);
+    /// # })
+    /// ```
+    fn reorder_by_key<K, F>(self, key_fn: F) -> ReorderByKey<Self, K, Self::IndexedItem, F>
+    where
+        K: Hash + Eq,
+        F: FnMut(&Self::IndexedItem) -> K;
 }
 
 impl<S, T> IndexStreamExt for S
@@ -56,12 +148,86 @@ where
     fn reorder_enumerated(self) -> ReorderEnumerated<Self, Self::IndexedItem> {
         ReorderEnumerated {
             commit: 0,
+            high_water_mark: 0,
+            buffer: HashMap::new(),
+            stream: self,
+        }
+    }
+
+    fn reorder_enumerated_dense(self) -> ReorderEnumeratedDense<Self, Self::IndexedItem> {
+        ReorderEnumeratedDense {
+            commit: 0,
+            len: 0,
+            high_water_mark: 0,
+            buffer: VecDeque::new(),
+            stream: self,
+        }
+    }
+
+    fn reorder_enumerated_checked(
+        self,
+        start: usize,
+    ) -> ReorderEnumeratedChecked<Self, Self::IndexedItem> {
+        ReorderEnumeratedChecked {
+            start,
+            commit: start,
+            is_terminated: false,
             buffer: HashMap::new(),
             stream: self,
         }
     }
+
+    fn reorder_by_key<K, F>(self, key_fn: F) -> ReorderByKey<Self, K, Self::IndexedItem, F>
+    where
+        K: Hash + Eq,
+        F: FnMut(&Self::IndexedItem) -> K,
+    {
+        ReorderByKey {
+            key_fn,
+            keys: HashMap::new(),
+            counters: None,
+            ready: VecDeque::new(),
+            stream: self,
+        }
+    }
 }
 
+/// The error from [reorder_enumerated_checked()](IndexStreamExt::reorder_enumerated_checked).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ReorderError {
+    /// The index number appears more than once.
+    DuplicateIndex(usize),
+    /// The index number is less than the start index.
+    IndexBeforeStart { index: usize, start: usize },
+    /// The input ended while items are waiting for the missing index number.
+    Gap { missing: usize, num_buffered: usize },
+}
+
+impl Display for ReorderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::DuplicateIndex(index) => {
+                write!(f, "the index number {} appears more than once", index)
+            }
+            Self::IndexBeforeStart { index, start } => write!(
+                f,
+                "the index number {} is less than the start index {}",
+                index, start
+            ),
+            Self::Gap {
+                missing,
+                num_buffered,
+            } => write!(
+                f,
+                "the item for index number {} is missing, {} items are left behind",
+                missing, num_buffered
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReorderError {}
+
 // reorder_enumerated
 
 pub use reorder_enumerated::*;
@@ -78,11 +244,19 @@ mod reorder_enumerated {
         S: ?Sized,
     {
         pub(super) commit: usize,
+        pub(super) high_water_mark: usize,
         pub(super) buffer: HashMap<usize, T>,
         #[pin]
         pub(super) stream: S,
     }
 
+    impl<S, T> ReorderEnumerated<S, T> {
+        /// Returns the maximum number of items that were buffered for reordering so far.
+        pub fn high_water_mark(&self) -> usize {
+            self.high_water_mark
+        }
+    }
+
     impl<S, T> Stream for ReorderEnumerated<S, T>
     where
         S: Stream<Item = (usize, T)>,
@@ -106,6 +280,8 @@ mod reorder_enumerated {
                                     "the index number {} appears more than once",
                                     index
                                 );
+                                *this.high_water_mark =
+                                    cmp::max(*this.high_water_mark, this.buffer.len());
                             }
                             Equal => {
                                 *this.commit += 1;
@@ -129,3 +305,503 @@ mod reorder_enumerated {
         }
     }
 }
+
+// reorder_enumerated_dense
+
+pub use reorder_enumerated_dense::*;
+
+mod reorder_enumerated_dense {
+    use super::*;
+
+    /// Stream for the [reorder_enumerated_dense](IndexStreamExt::reorder_enumerated_dense) method.
+    #[derive(Derivative)]
+    #[derivative(Debug)]
+    #[pin_project]
+    pub struct ReorderEnumeratedDense<S, T>
+    where
+        S: ?Sized,
+    {
+        pub(super) commit: usize,
+        pub(super) len: usize,
+        pub(super) high_water_mark: usize,
+        /// The slot at position `i` holds the item for index `commit + i`.
+        pub(super) buffer: VecDeque<Option<T>>,
+        #[pin]
+        pub(super) stream: S,
+    }
+
+    impl<S, T> ReorderEnumeratedDense<S, T> {
+        /// Returns the maximum number of items that were buffered for reordering so far.
+        pub fn high_water_mark(&self) -> usize {
+            self.high_water_mark
+        }
+    }
+
+    impl<S, T> Stream for ReorderEnumeratedDense<S, T>
+    where
+        S: Stream<Item = (usize, T)>,
+    {
+        type Item = T;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+            let mut this = self.project();
+
+            Ready(loop {
+                if let Some(Some(_)) = this.buffer.front() {
+                    let item = this.buffer.pop_front().unwrap().unwrap();
+                    *this.commit += 1;
+                    *this.len -= 1;
+                    break Some(item);
+                } else {
+                    match ready!(Pin::new(&mut this.stream).poll_next(cx)) {
+                        Some((index, item)) => match (*this.commit).cmp(&index) {
+                            Less => {
+                                let offset = index - *this.commit;
+
+                                if offset >= this.buffer.len() {
+                                    this.buffer.resize_with(offset + 1, || None);
+                                }
+
+                                let prev = this.buffer[offset].replace(item);
+                                assert!(
+                                    prev.is_none(),
+                                    "the index number {} appears more than once",
+                                    index
+                                );
+                                *this.len += 1;
+                                *this.high_water_mark = cmp::max(*this.high_water_mark, *this.len);
+                            }
+                            Equal => {
+                                // the front slot is vacant if exists
+                                this.buffer.pop_front();
+                                *this.commit += 1;
+                                break Some(item);
+                            }
+                            Greater => {
+                                panic!("the index number {} appears more than once", index);
+                            }
+                        },
+                        None => {
+                            assert!(
+                                *this.len == 0,
+                                "the item for index number {} is missing",
+                                this.commit
+                            );
+                            break None;
+                        }
+                    }
+                }
+            })
+        }
+    }
+}
+
+// reorder_enumerated_checked
+
+pub use reorder_enumerated_checked::*;
+
+mod reorder_enumerated_checked {
+    use super::*;
+
+    /// Stream for the [reorder_enumerated_checked](IndexStreamExt::reorder_enumerated_checked) method.
+    #[derive(Derivative)]
+    #[derivative(Debug)]
+    #[pin_project]
+    pub struct ReorderEnumeratedChecked<S, T>
+    where
+        S: ?Sized,
+    {
+        pub(super) start: usize,
+        pub(super) commit: usize,
+        pub(super) is_terminated: bool,
+        pub(super) buffer: HashMap<usize, T>,
+        #[pin]
+        pub(super) stream: S,
+    }
+
+    impl<S, T> Stream for ReorderEnumeratedChecked<S, T>
+    where
+        S: Stream<Item = (usize, T)>,
+    {
+        type Item = Result<T, ReorderError>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+            let mut this = self.project();
+
+            if *this.is_terminated {
+                return Ready(None);
+            }
+
+            let result = loop {
+                if let Some(item) = this.buffer.remove(&*this.commit) {
+                    *this.commit += 1;
+                    break Ok(item);
+                }
+
+                match ready!(Pin::new(&mut this.stream).poll_next(cx)) {
+                    Some((index, item)) => {
+                        if index < *this.start {
+                            break Err(ReorderError::IndexBeforeStart {
+                                index,
+                                start: *this.start,
+                            });
+                        }
+
+                        match (*this.commit).cmp(&index) {
+                            Less => {
+                                if this.buffer.insert(index, item).is_some() {
+                                    break Err(ReorderError::DuplicateIndex(index));
+                                }
+                            }
+                            Equal => {
+                                *this.commit += 1;
+                                break Ok(item);
+                            }
+                            Greater => break Err(ReorderError::DuplicateIndex(index)),
+                        }
+                    }
+                    None => {
+                        *this.is_terminated = true;
+
+                        if this.buffer.is_empty() {
+                            return Ready(None);
+                        } else {
+                            break Err(ReorderError::Gap {
+                                missing: *this.commit,
+                                num_buffered: this.buffer.len(),
+                            });
+                        }
+                    }
+                }
+            };
+
+            if result.is_err() {
+                *this.is_terminated = true;
+                this.buffer.clear();
+            }
+
+            Ready(Some(result))
+        }
+    }
+
+    impl<S, T> FusedStream for ReorderEnumeratedChecked<S, T>
+    where
+        Self: Stream,
+    {
+        fn is_terminated(&self) -> bool {
+            self.is_terminated
+        }
+    }
+}
+
+// reorder_by_key
+
+pub use reorder_by_key::*;
+
+mod reorder_by_key {
+    use super::*;
+
+    /// Stream for the [reorder_by_key](IndexStreamExt::reorder_by_key) method.
+    #[derive(Derivative)]
+    #[derivative(Debug)]
+    #[pin_project]
+    pub struct ReorderByKey<S, K, T, F>
+    where
+        S: ?Sized,
+    {
+        #[derivative(Debug = "ignore")]
+        pub(super) key_fn: F,
+        pub(super) keys: HashMap<K, KeyState<T>>,
+        #[derivative(Debug = "ignore")]
+        pub(super) counters: Option<KeyCounters<K>>,
+        /// Buffered items released by the arrival of a missing index.
+        pub(super) ready: VecDeque<T>,
+        #[pin]
+        pub(super) stream: S,
+    }
+
+    /// The next index number of each key, shared with the side numbering the items.
+    pub(crate) type KeyCounters<K> = Arc<Mutex<HashMap<K, usize>>>;
+
+    impl<S, K, T, F> ReorderByKey<S, K, T, F> {
+        /// Creates the stream that forgets a key once all numbered items of the key are
+        /// yielded.
+        ///
+        /// The numbering side takes the index from `counters` under the lock. A forgotten key
+        /// is removed from `counters` as well, so that its index numbers restart from zero.
+        pub(crate) fn with_counters(stream: S, key_fn: F, counters: KeyCounters<K>) -> Self {
+            Self {
+                key_fn,
+                keys: HashMap::new(),
+                counters: Some(counters),
+                ready: VecDeque::new(),
+                stream,
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub(super) struct KeyState<T> {
+        commit: usize,
+        buffer: HashMap<usize, T>,
+    }
+
+    impl<T> Default for KeyState<T> {
+        fn default() -> Self {
+            Self {
+                commit: 0,
+                buffer: HashMap::new(),
+            }
+        }
+    }
+
+    impl<S, K, T, F> Stream for ReorderByKey<S, K, T, F>
+    where
+        S: Stream<Item = (usize, T)>,
+        K: Hash + Eq,
+        F: FnMut(&T) -> K,
+    {
+        type Item = T;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+            let mut this = self.project();
+
+            Ready(loop {
+                if let Some(item) = this.ready.pop_front() {
+                    break Some(item);
+                }
+
+                match ready!(Pin::new(&mut this.stream).poll_next(cx)) {
+                    Some((index, item)) => {
+                        let key = (this.key_fn)(&item);
+                        let commit = this.keys.get(&key).map_or(0, |state| state.commit);
+
+                        match commit.cmp(&index) {
+                            Less => {
+                                let state = this.keys.entry(key).or_default();
+                                let prev = state.buffer.insert(index, item);
+                                assert!(
+                                    prev.is_none(),
+                                    "the index number {} appears more than once",
+                                    index
+                                );
+                            }
+                            Equal => {
+                                let commit = match this.keys.get_mut(&key) {
+                                    Some(state) => {
+                                        state.commit += 1;
+
+                                        while let Some(next) = state.buffer.remove(&state.commit) {
+                                            state.commit += 1;
+                                            this.ready.push_back(next);
+                                        }
+
+                                        state.commit
+                                    }
+                                    None => 1,
+                                };
+
+                                // forget the key if no more items of the key are numbered
+                                let is_drained = match this.counters {
+                                    Some(counters) => {
+                                        let mut counters = counters.lock().unwrap();
+                                        let is_drained = counters.get(&key) == Some(&commit);
+                                        if is_drained {
+                                            counters.remove(&key);
+                                        }
+                                        is_drained
+                                    }
+                                    None => false,
+                                };
+
+                                if is_drained {
+                                    this.keys.remove(&key);
+                                } else {
+                                    this.keys.entry(key).or_insert_with(|| KeyState {
+                                        commit,
+                                        buffer: HashMap::new(),
+                                    });
+                                }
+
+                                break Some(item);
+                            }
+                            Greater => {
+                                panic!("the index number {} appears more than once", index);
+                            }
+                        }
+                    }
+                    None => {
+                        let missing = this
+                            .keys
+                            .values()
+                            .find(|state| !state.buffer.is_empty())
+                            .map(|state| state.commit);
+                        if let Some(missing) = missing {
+                            panic!("the item for index number {} is missing", missing);
+                        }
+                        break None;
+                    }
+                }
+            })
+        }
+    }
+}
+
+// tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::async_test;
+    use rand::prelude::*;
+
+    async_test! {
+        async fn reorder_enumerated_dense_growth_test() {
+            // the last index arrives first, so that the buffer grows to hold all others
+            let mut stream = stream::iter((0..100).rev().map(|index| (index, index)))
+                .reorder_enumerated_dense();
+            let output: Vec<_> = (&mut stream).collect().await;
+
+            itertools::assert_equal(output, 0..100);
+            assert_eq!(stream.high_water_mark(), 99);
+            assert!(stream.buffer.is_empty());
+        }
+
+
+        async fn reorder_enumerated_dense_wrap_around_test() {
+            const LEN: usize = 10_000;
+            const WINDOW: usize = 16;
+
+            // shuffle within windows, so that the slots are filled out of order while the
+            // front of the ring keeps advancing and wraps around the storage many times
+            let mut rng = StdRng::seed_from_u64(0);
+            let mut indices: Vec<_> = (0..LEN).collect();
+            indices
+                .chunks_mut(WINDOW)
+                .for_each(|chunk| chunk.shuffle(&mut rng));
+
+            let mut stream = stream::iter(indices.into_iter().map(|index| (index, index * 2)))
+                .reorder_enumerated_dense();
+            let output: Vec<_> = (&mut stream).collect().await;
+
+            itertools::assert_equal(output, (0..LEN).map(|index| index * 2));
+            assert!(stream.high_water_mark() < WINDOW);
+            assert!(stream.buffer.capacity() < WINDOW * 2);
+        }
+
+
+        async fn reorder_enumerated_checked_duplicate_test() {
+            // the duplicate of a buffered index
+            let values: Vec<_> = stream::iter([(1, 'b'), (1, 'c'), (0, 'a'), (2, 'd')])
+                .reorder_enumerated_checked(0)
+                .collect()
+                .await;
+            assert_eq!(values, [Err(ReorderError::DuplicateIndex(1))]);
+
+            // the duplicate of a yielded index
+            let values: Vec<_> = stream::iter([(0, 'a'), (1, 'b'), (0, 'c'), (2, 'd')])
+                .reorder_enumerated_checked(0)
+                .collect()
+                .await;
+            assert_eq!(
+                values,
+                [Ok('a'), Ok('b'), Err(ReorderError::DuplicateIndex(0))]
+            );
+        }
+
+
+        async fn reorder_enumerated_checked_before_start_test() {
+            let values: Vec<_> = stream::iter([(5, 'a'), (3, 'b'), (6, 'c')])
+                .reorder_enumerated_checked(5)
+                .collect()
+                .await;
+            assert_eq!(
+                values,
+                [
+                    Ok('a'),
+                    Err(ReorderError::IndexBeforeStart { index: 3, start: 5 })
+                ]
+            );
+        }
+
+
+        async fn reorder_enumerated_checked_terminate_test() {
+            // the input is not polled again once an error is yielded
+            let mut stream = stream::iter([(1, 'b'), (1, 'c')])
+                .chain(stream::poll_fn(|_| -> Poll<Option<(usize, char)>> {
+                    panic!("the input is polled after the error")
+                }))
+                .reorder_enumerated_checked(0);
+
+            assert_eq!(
+                stream.next().await,
+                Some(Err(ReorderError::DuplicateIndex(1)))
+            );
+            assert!(stream.is_terminated());
+            assert_eq!(stream.next().await, None);
+            assert_eq!(stream.next().await, None);
+
+            // the same holds for the error at the end of the input
+            let mut stream = stream::iter([(1, 'b')]).reorder_enumerated_checked(0);
+
+            assert_eq!(
+                stream.next().await,
+                Some(Err(ReorderError::Gap {
+                    missing: 0,
+                    num_buffered: 1
+                }))
+            );
+            assert!(stream.is_terminated());
+            assert_eq!(stream.next().await, None);
+        }
+
+
+        async fn reorder_by_key_with_counters_test() {
+            let counters: KeyCounters<char> = Arc::default();
+            let number = {
+                let counters = counters.clone();
+                move |key: char| {
+                    let mut counters = counters.lock().unwrap();
+                    let counter = counters.entry(key).or_insert(0);
+                    let index = *counter;
+                    *counter += 1;
+                    (index, (key, index))
+                }
+            };
+            let (tx, rx) = flume::unbounded();
+            let mut stream = ReorderByKey::with_counters(
+                rx.into_stream(),
+                |&(key, _): &(char, usize)| key,
+                counters.clone(),
+            );
+
+            // the second item of 'a' arrives first and waits for the first one
+            let (a0, a1, b0) = (number('a'), number('a'), number('b'));
+            tx.send(a1).unwrap();
+            tx.send(b0).unwrap();
+            tx.send(a0).unwrap();
+            assert_eq!(stream.next().await, Some(('b', 0)));
+            assert_eq!(stream.next().await, Some(('a', 0)));
+            assert_eq!(stream.next().await, Some(('a', 1)));
+
+            // the drained keys are forgotten on both sides
+            assert!(stream.keys.is_empty());
+            assert!(counters.lock().unwrap().is_empty());
+
+            // the index numbers of a forgotten key restart from zero, and a numbered item
+            // keeps the key until it is yielded
+            let (a0, a1) = (number('a'), number('a'));
+            assert_eq!(a0.0, 0);
+            tx.send(a0).unwrap();
+            assert_eq!(stream.next().await, Some(('a', 0)));
+            assert_eq!(stream.keys.len(), 1);
+
+            tx.send(a1).unwrap();
+            drop(tx);
+            assert_eq!(stream.next().await, Some(('a', 1)));
+            assert_eq!(stream.next().await, None);
+            assert!(stream.keys.is_empty());
+            assert!(counters.lock().unwrap().is_empty());
+        }
+    }
+}
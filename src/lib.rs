@@ -7,6 +7,7 @@
 //!
 //! - `runtime-tokio` enables the [tokio] multi-threaded runtime.
 //! - `runtime-async-std` enables the [async-std](async_std) default runtime.
+//! - `runtime-smol` enables the [smol] executor with one thread per CPU.
 //!
 //! Please read [Using Custom Runtime](#using-custom-runtime) if you would like to provide a custom runtime.
 //!
@@ -51,6 +52,22 @@
 //! - [`par_then_unordered()`](ParStreamExt::par_then_unordered) runs parallel asynchronous task without respecting input order.
 //! - [`try_par_map()`](TryParStreamExt::try_par_map), [`try_par_then()`](TryParStreamExt::try_par_then),
 //!   [`try_par_then_unordered()`](TryParStreamExt::try_par_then_unordered) are the fallible variances.
+//! - [`par_then_keyed()`](ParStreamExt::par_then_keyed) respects input order only among items of the same key.
+//! - [`par_then_sharded()`](ParStreamExt::par_then_sharded) routes the items of the same key to the same worker.
+//! - [`par_map_init()`](ParStreamExt::par_map_init) and [`par_then_init()`](ParStreamExt::par_then_init)
+//!   keep a state per worker created by an init function.
+//! - [`par_filter()`](ParStreamExt::par_filter), [`par_filter_map()`](ParStreamExt::par_filter_map) and
+//!   [`par_flat_map()`](ParStreamExt::par_flat_map) produce zero or many items per input, with unordered variants.
+//! - [`par_fold()`](ParStreamExt::par_fold) reduces the items in parallel respecting input order, for non-commutative operations.
+//! - [`try_par_reduce()`](TryParStreamExt::try_par_reduce) and [`try_par_fold()`](TryParStreamExt::try_par_fold) are the fallible reductions.
+//! - [`par_sort_by()`](ParStreamExt::par_sort_by) and its relatives sort the items in parallel chunks and merge them,
+//!   optionally spilling the chunks to disk.
+//! - [`par_fold_by_key()`](ParStreamExt::par_fold_by_key) folds the items into per-key accumulators in parallel.
+//! - [`par_then_timeout()`](ParStreamExt::par_then_timeout) and [`try_par_then_timeout()`](TryParStreamExt::try_par_then_timeout)
+//!   give each task a deadline built on [`rt::timeout()`](rt::timeout).
+//! - [`try_par_then_retry()`](TryParStreamExt::try_par_then_retry) re-runs failed tasks according to a [`RetryPolicy`](RetryPolicy).
+//! - [`try_par_then_with_error_policy()`](TryParStreamExt::try_par_then_with_error_policy) and its relatives
+//!   keep going after errors according to an [`ErrorPolicy`](ErrorPolicy) instead of stopping on the first one.
 //!
 //! Chaining the combinators above establishes a parallel processing dataflow.
 //!
@@ -78,6 +95,11 @@
 //! - [`reorder_enumerated()`](IndexStreamExt::reorder_enumerated) reorders the items `(index, value)`
 //!   according to the index number.
 //! - [`try_reorder_enumerated()`](TryIndexStreamExt::try_reorder_enumerated) is the fallible coutnerpart.
+//! - [`reorder_enumerated_dense()`](IndexStreamExt::reorder_enumerated_dense) is a faster ring buffer
+//!   based variant for indices arriving close to each other.
+//! - [`reorder_enumerated_checked()`](IndexStreamExt::reorder_enumerated_checked) reports duplicated,
+//!   out-of-range and missing index numbers as errors.
+//! - [`reorder_by_key()`](IndexStreamExt::reorder_by_key) reorders the items within each key.
 //!
 //! They can be combined with either
 //! [enumerate()](futures::StreamExt::enumerate) from [futures] crate or the fallible counterpart
@@ -218,6 +240,11 @@
 //! - `2.0` (floating number): sets the worker size to the scaling of logical system processors, and buffer size is contant multiple of worker size.
 //! - [`ParParamsConfig`](ParParamsConfig): manual configuration.
 //!
+//! The [`cancel`](ParParams::cancel) field of [`ParParams`](ParParams) accepts a [`CancellationToken`](CancellationToken).
+//! Cancelling the token stops the parallel workers and terminates the output stream.
+//! The [`panic_policy`](ParParams::panic_policy) field decides whether a panicking task
+//! resumes the panic on the consumer or is skipped.
+//!
 //! # Utility Combinators
 //!
 //! The crate provides several utility stream combinators that coule make your life easier :).
@@ -240,7 +267,7 @@
 //! ```
 //! use futures::future::BoxFuture;
 //! use par_stream::rt::{Runtime, SleepHandle, SpawnHandle};
-//! use std::{any::Any, time::Duration};
+//! use std::time::Duration;
 //!
 //! pub struct MyRuntime {/* omit */}
 //!
@@ -250,32 +277,20 @@
 //!     }
 //! }
 //!
-//! unsafe impl Runtime for MyRuntime {
-//!     fn block_on<'a>(
-//!         &self,
-//!         fut: BoxFuture<'a, Box<dyn Send + Any + 'static>>,
-//!     ) -> Box<dyn Send + Any + 'static> {
+//! impl Runtime for MyRuntime {
+//!     fn block_on(&self, fut: BoxFuture<'_, ()>) {
 //!         todo!()
 //!     }
 //!
-//!     fn block_on_executor<'a>(
-//!         &self,
-//!         fut: BoxFuture<'a, Box<dyn Send + Any + 'static>>,
-//!     ) -> Box<dyn Send + Any + 'static> {
+//!     fn block_on_executor(&self, fut: BoxFuture<'_, ()>) {
 //!         todo!()
 //!     }
 //!
-//!     fn spawn(
-//!         &self,
-//!         fut: BoxFuture<'static, Box<dyn Send + Any + 'static>>,
-//!     ) -> Box<dyn SpawnHandle> {
+//!     fn spawn(&self, fut: BoxFuture<'static, ()>) -> Box<dyn SpawnHandle> {
 //!         todo!()
 //!     }
 //!
-//!     fn spawn_blocking(
-//!         &self,
-//!         f: Box<dyn FnOnce() -> Box<dyn Send + Any + 'static> + Send>,
-//!     ) -> Box<dyn SpawnHandle> {
+//!     fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) -> Box<dyn SpawnHandle> {
 //!         todo!()
 //!     }
 //!
@@ -289,14 +304,18 @@
 
 mod broadcast;
 pub mod builder;
+mod cancel;
 mod common;
 mod config;
 mod functions;
 mod index_stream;
+mod panic;
 mod par_stream;
 mod pull;
+mod retry;
 pub mod rt;
 mod shared_stream;
+mod sort;
 pub mod state_stream;
 mod stream;
 mod tee;
@@ -307,11 +326,15 @@ mod utils;
 
 pub use crate::par_stream::*;
 pub use broadcast::*;
+pub use cancel::*;
 pub use config::*;
 pub use functions::*;
 pub use index_stream::*;
+pub use panic::*;
 pub use pull::*;
+pub use retry::*;
 pub use shared_stream::*;
+pub use sort::Spill;
 pub use stream::*;
 pub use tee::*;
 pub use try_index_stream::*;
@@ -0,0 +1,168 @@
+use crate::{common::*, config::PanicPolicy};
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+};
+
+/// The payload of a panic caught from a parallel worker.
+///
+/// It is produced by [par_then_catch_unwind()](crate::ParStreamExt::par_then_catch_unwind)
+/// and its relatives in place of the output item of a panicking task.
+pub struct PanicPayload {
+    payload: Box<dyn Any + Send + 'static>,
+}
+
+impl PanicPayload {
+    /// Wraps the payload returned by [catch_unwind()](std::panic::catch_unwind).
+    pub fn new(payload: Box<dyn Any + Send + 'static>) -> Self {
+        Self { payload }
+    }
+
+    /// Returns the panic message if the payload is a string.
+    pub fn message(&self) -> Option<&str> {
+        if let Some(msg) = self.payload.downcast_ref::<&'static str>() {
+            Some(msg)
+        } else {
+            self.payload
+                .downcast_ref::<String>()
+                .map(|msg| msg.as_str())
+        }
+    }
+
+    /// Unwraps the original payload.
+    pub fn into_inner(self) -> Box<dyn Any + Send + 'static> {
+        self.payload
+    }
+
+    /// Resumes the panic with the original payload.
+    pub fn resume_unwind(self) -> ! {
+        panic::resume_unwind(self.payload)
+    }
+}
+
+impl Debug for PanicPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PanicPayload")
+            .field(&self.message())
+            .finish()
+    }
+}
+
+impl Display for PanicPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.message() {
+            Some(msg) => write!(f, "the task panicked: {}", msg),
+            None => write!(f, "the task panicked"),
+        }
+    }
+}
+
+impl std::error::Error for PanicPayload {}
+
+/// Calls the function and catches the panic.
+pub(crate) fn catch_unwind<T, F>(f: F) -> Result<T, PanicPayload>
+where
+    F: FnOnce() -> T,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(PanicPayload::new)
+}
+
+/// Runs the future created by [catch_unwind()] and catches the panic from the future.
+pub(crate) async fn catch_unwind_future<Fut>(
+    fut: Result<Fut, PanicPayload>,
+) -> Result<Fut::Output, PanicPayload>
+where
+    Fut: Future,
+{
+    AssertUnwindSafe(fut?)
+        .catch_unwind()
+        .await
+        .map_err(PanicPayload::new)
+}
+
+/// Runs the stream created by [catch_unwind()] and catches the panic from the stream.
+///
+/// The stream ends after the item carrying the panic.
+pub(crate) fn catch_unwind_stream<St>(
+    stream: Result<St, PanicPayload>,
+) -> impl Stream<Item = Result<St::Item, PanicPayload>>
+where
+    St: Stream,
+{
+    match stream {
+        Ok(stream) => AssertUnwindSafe(stream)
+            .catch_unwind()
+            .map(|result| result.map_err(PanicPayload::new))
+            .left_stream(),
+        Err(payload) => stream::once(future::ready(Err(payload))).right_stream(),
+    }
+}
+
+/// Resumes the panic or discards the item according to the policy.
+pub(crate) fn apply_policy<T>(policy: PanicPolicy, result: Result<T, PanicPayload>) -> Option<T> {
+    match result {
+        Ok(item) => Some(item),
+        Err(payload) => match policy {
+            PanicPolicy::Propagate => payload.resume_unwind(),
+            PanicPolicy::Skip => None,
+        },
+    }
+}
+
+// resume_unwind
+
+pub use resume_unwind::*;
+
+mod resume_unwind {
+    use super::*;
+
+    /// Stream that applies the [PanicPolicy] on the items caught by `catch_unwind`.
+    ///
+    /// A `Err(PanicPayload)` item resumes the panic if the policy is [PanicPolicy::Propagate],
+    /// or is discarded if the policy is [PanicPolicy::Skip].
+    #[derive(Derivative)]
+    #[derivative(Debug)]
+    #[pin_project]
+    pub struct ResumeUnwind<S> {
+        pub(crate) policy: PanicPolicy,
+        #[pin]
+        pub(crate) stream: S,
+    }
+
+    impl<S> ResumeUnwind<S> {
+        pub(crate) fn new(stream: S, policy: PanicPolicy) -> Self {
+            Self { policy, stream }
+        }
+    }
+
+    impl<S, T> Stream for ResumeUnwind<S>
+    where
+        S: Stream<Item = Result<T, PanicPayload>>,
+    {
+        type Item = T;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let mut this = self.project();
+
+            Ready(loop {
+                match ready!(this.stream.as_mut().poll_next(cx)) {
+                    Some(result) => {
+                        if let Some(item) = apply_policy(*this.policy, result) {
+                            break Some(item);
+                        }
+                    }
+                    None => break None,
+                }
+            })
+        }
+    }
+
+    impl<S, T> FusedStream for ResumeUnwind<S>
+    where
+        S: FusedStream<Item = Result<T, PanicPayload>>,
+    {
+        fn is_terminated(&self) -> bool {
+            self.stream.is_terminated()
+        }
+    }
+}
@@ -1,22 +1,82 @@
 use crate::{
     broadcast::BroadcastBuilder,
     builder::ParBuilder,
+    cancel::Cancelled,
     common::*,
     config::{BufSize, ParParams},
-    index_stream::{IndexStreamExt as _, ReorderEnumerated},
+    functions::iter_blocking_on,
+    index_stream::{IndexStreamExt as _, KeyCounters, ReorderByKey, ReorderEnumeratedDense},
+    panic::{self, PanicPayload, ResumeUnwind},
     pull::PullBuilder,
-    rt,
+    rt::{self, Elapsed, RuntimeHandle},
+    sort::{self, KMerge, RunReader, Spill},
     stream::StreamExt as _,
     tee::Tee,
     utils,
 };
 use flume::r#async::RecvStream;
+use futures::stream::TakeUntil;
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use tokio::sync::watch;
 
 /// Stream for the [par_then()](ParStreamExt::par_then) method.
-pub type ParThen<T> = ReorderEnumerated<RecvStream<'static, (usize, T)>, T>;
+pub type ParThen<T> = ResumeUnwind<ParThenCatchUnwind<T>>;
+
+/// Stream for the [par_then_catch_unwind()](ParStreamExt::par_then_catch_unwind) method.
+pub type ParThenCatchUnwind<T> = Reordered<T>;
 
 /// Stream for the [par_map()](ParStreamExt::par_map) method.
-pub type ParMap<T> = ReorderEnumerated<RecvStream<'static, (usize, T)>, T>;
+pub type ParMap<T> = ResumeUnwind<ParMapCatchUnwind<T>>;
+
+/// Stream for the [par_map_catch_unwind()](ParStreamExt::par_map_catch_unwind) method.
+pub type ParMapCatchUnwind<T> = Reordered<T>;
+
+/// Stream for the [par_then_unordered()](ParStreamExt::par_then_unordered) method.
+pub type ParThenUnordered<T> = ResumeUnwind<ParThenUnorderedCatchUnwind<T>>;
+
+/// Stream for the [par_then_unordered_catch_unwind()](ParStreamExt::par_then_unordered_catch_unwind)
+/// method.
+pub type ParThenUnorderedCatchUnwind<T> = RecvStream<'static, Result<T, PanicPayload>>;
+
+/// Stream for the [par_map_unordered()](ParStreamExt::par_map_unordered) method.
+pub type ParMapUnordered<T> = ResumeUnwind<ParMapUnorderedCatchUnwind<T>>;
+
+/// Stream for the [par_map_unordered_catch_unwind()](ParStreamExt::par_map_unordered_catch_unwind)
+/// method.
+pub type ParMapUnorderedCatchUnwind<T> = RecvStream<'static, Result<T, PanicPayload>>;
+
+/// Stream for the [par_flat_map_unordered()](ParStreamExt::par_flat_map_unordered) method.
+pub type ParFlatMapUnordered<T> = ResumeUnwind<RecvStream<'static, Result<T, PanicPayload>>>;
+
+/// Stream for the [par_then_keyed()](ParStreamExt::par_then_keyed) method.
+pub type ParThenKeyed<T> = ResumeUnwind<BoxStream<'static, Result<T, PanicPayload>>>;
+
+/// Stream for the [par_then_sharded()](ParStreamExt::par_then_sharded) method.
+pub type ParThenSharded<T> = ResumeUnwind<RecvStream<'static, Result<T, PanicPayload>>>;
+
+/// Stream for the [par_filter()](ParStreamExt::par_filter) and
+/// [par_filter_map()](ParStreamExt::par_filter_map) methods.
+pub type ParFilterMap<T> = stream::FilterMap<
+    ParThen<Option<T>>,
+    future::Ready<Option<T>>,
+    fn(Option<T>) -> future::Ready<Option<T>>,
+>;
+
+/// Stream for the [par_filter_unordered()](ParStreamExt::par_filter_unordered) and
+/// [par_filter_map_unordered()](ParStreamExt::par_filter_map_unordered) methods.
+pub type ParFilterMapUnordered<T> = stream::FilterMap<
+    ParThenUnordered<Option<T>>,
+    future::Ready<Option<T>>,
+    fn(Option<T>) -> future::Ready<Option<T>>,
+>;
+
+/// Stream for the [par_flat_map()](ParStreamExt::par_flat_map) and
+/// [par_flat_map_iter()](ParStreamExt::par_flat_map_iter) methods.
+pub type ParFlatMap<T> = ResumeUnwind<Flattened<T>>;
 
 /// The trait extends [Stream](futures::stream::Stream) types with parallel processing combinators.
 pub trait ParStreamExt
@@ -60,6 +120,26 @@ where
     where
         B: Into<BufSize>;
 
+    /// Moves the stream to a spawned worker like [spawned()](ParStreamExt::spawned), and
+    /// aborts the worker once the returned stream and all its clones are dropped.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let mut stream = stream::iter(0..).spawned_abortable(None);
+    /// let values: Vec<_> = (&mut stream).take(3).collect().await;
+    /// assert_eq!(values, [0, 1, 2]);
+    ///
+    /// // stops the worker producing the endless stream
+    /// drop(stream);
+    /// # })
+    /// ```
+    fn spawned_abortable<B>(self, buf_size: B) -> Spawned<Self::Item>
+    where
+        B: Into<BufSize>;
+
     /// Maps this stream’s items to a different type on an blocking thread.
     ///
     /// The combinator iteratively maps the stream items and places the output
@@ -132,6 +212,143 @@ where
         T: 'static + Send,
         P: Into<ParParams>;
 
+    /// Runs an asynchronous task on parallel workers, routing the items of the same key
+    /// to the same worker.
+    ///
+    /// It spawns [num_workers](ParParams::num_workers) workers. Each input item is hashed by the key
+    /// computed by `key_fn` and forwarded to a fixed worker through a dedicated channel, similar to
+    /// [pull_routing()](ParStreamExt::pull_routing). Each worker owns a clone of `f` and calls it
+    /// with the worker index and the item, awaiting the futures one by one.
+    ///
+    /// Because a key always goes to the same worker, the state captured by `f` can be updated per
+    /// key without locks, and the items of the same key are processed in input order. Outputs of
+    /// different workers are produced without respecting input order.
+    ///
+    /// # Panics
+    /// The [num_workers](ParParams::num_workers) must be positive.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    /// use std::collections::HashMap;
+    ///
+    /// let transfers = vec![("alice", 3), ("bob", 1), ("alice", 4), ("bob", 5)];
+    ///
+    /// let balances: HashMap<_, _> = stream::iter(transfers)
+    ///     .par_then_sharded(None, |&(account, _)| account, {
+    ///         // each worker owns its copy of the map
+    ///         let mut balances = HashMap::new();
+    ///
+    ///         move |_worker_index, (account, amount)| {
+    ///             let balance = balances.entry(account).or_insert(0);
+    ///             *balance += amount;
+    ///             let balance = *balance;
+    ///             async move { (account, balance) }
+    ///         }
+    ///     })
+    ///     .collect()
+    ///     .await;
+    ///
+    /// assert_eq!(balances["alice"], 7);
+    /// assert_eq!(balances["bob"], 6);
+    /// # })
+    /// ```
+    fn par_then_sharded<K, T, P, KF, F, Fut>(
+        self,
+        params: P,
+        key_fn: KF,
+        f: F,
+    ) -> ParThenSharded<T>
+    where
+        K: Hash,
+        T: 'static + Send,
+        KF: 'static + FnMut(&Self::Item) -> K + Send,
+        F: 'static + Send + Clone + FnMut(usize, Self::Item) -> Fut,
+        Fut: 'static + Future<Output = T> + Send,
+        P: Into<ParParams>;
+
+    /// Runs an asynchronous task on parallel workers with per-worker state and produces items
+    /// respecting the input order.
+    ///
+    /// Each worker creates its state once by calling `init` with the worker index. The function
+    /// `f` takes the state by value together with the item, and the future gives the state back
+    /// along with the output, so that the future can use the state across await points.
+    ///
+    /// If a task panics, the state of that worker is lost and is re-created by `init` for the
+    /// next item.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let lines: Vec<_> = stream::iter(0..100)
+    ///     .par_then_init(
+    ///         None,
+    ///         // a scratch buffer allocated once per worker
+    ///         |_worker_index| String::with_capacity(64),
+    ///         |mut buf: String, value| async move {
+    ///             buf.clear();
+    ///             buf.push_str(&value.to_string());
+    ///             (buf.clone(), buf)
+    ///         },
+    ///     )
+    ///     .collect()
+    ///     .await;
+    /// let expect: Vec<_> = (0..100).map(|value| value.to_string()).collect();
+    /// assert_eq!(lines, expect);
+    /// # })
+    /// ```
+    fn par_then_init<T, P, State, InitF, F, Fut>(self, params: P, init: InitF, f: F) -> ParThen<T>
+    where
+        T: 'static + Send,
+        State: 'static + Send,
+        InitF: 'static + Send + Clone + FnMut(usize) -> State,
+        F: 'static + Send + Clone + FnMut(State, Self::Item) -> Fut,
+        Fut: 'static + Future<Output = (T, State)> + Send,
+        P: Into<ParParams>;
+
+    /// Runs a blocking task on parallel workers with per-worker state and produces items
+    /// respecting the input order.
+    ///
+    /// Each worker creates its state once by calling `init` with the worker index, and `f` is
+    /// called on the worker thread with a mutable reference to the state for each item. It
+    /// avoids recreating expensive resources, such as connections or scratch buffers, per item.
+    ///
+    /// If a task panics, the state of that worker is discarded and is re-created by `init` for
+    /// the next item.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let sums: Vec<_> = stream::iter(1..=100u64)
+    ///     .par_map_init(
+    ///         None,
+    ///         // a scratch buffer allocated once per worker
+    ///         |_worker_index| Vec::with_capacity(100),
+    ///         |buf: &mut Vec<u64>, value| {
+    ///             buf.clear();
+    ///             buf.extend(1..=value);
+    ///             buf.iter().sum::<u64>()
+    ///         },
+    ///     )
+    ///     .collect()
+    ///     .await;
+    /// let expect: Vec<_> = (1..=100).map(|value| value * (value + 1) / 2).collect();
+    /// assert_eq!(sums, expect);
+    /// # })
+    /// ```
+    fn par_map_init<T, P, State, InitF, F>(self, params: P, init: InitF, f: F) -> ParMap<T>
+    where
+        T: 'static + Send,
+        State: 'static,
+        InitF: 'static + Send + Clone + FnMut(usize) -> State,
+        F: 'static + Send + Clone + FnMut(&mut State, Self::Item) -> T,
+        P: Into<ParParams>;
+
     /// Converts the stream to cloneable receivers, each receiving a copy for each input item.
     ///
     /// It spawns a task to consume the stream, and forwards item copies to receivers.
@@ -204,6 +421,9 @@ where
     /// Each parallel worker shares the stream and executes a future for each input item.
     /// Output items are gathered to a channel and are reordered respecting to input order.
     ///
+    /// If a task panics, the [panic_policy](ParParams::panic_policy) decides whether the panic is
+    /// resumed on the consumer at the position of the item or the item is skipped.
+    ///
     /// ```rust
     /// # par_stream::rt::block_on_executor(async move {
     /// use futures::prelude::*;
@@ -226,6 +446,46 @@ where
         Fut: 'static + Future<Output = T> + Send,
         P: Into<ParParams>;
 
+    /// Runs an asynchronous task on parallel workers and produces items respecting the input order
+    /// within each key computed by `key_fn`.
+    ///
+    /// Outputs of the same key are produced in input order, while an output of one key may
+    /// overtake outputs of other keys. It allows more parallelism than [par_then()](ParStreamExt::par_then)
+    /// when only the order per entity matters. A key is forgotten once all its items taken so far
+    /// are yielded, so that the memory grows with the keys in flight rather than all keys seen.
+    ///
+    /// See [reorder_by_key()](crate::IndexStreamExt::reorder_by_key) for the underlying reordering.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let outputs: Vec<_> = stream::iter(0..100)
+    ///     .par_then_keyed(None, |value| value % 3, |value| async move { (value % 3, value) })
+    ///     .collect()
+    ///     .await;
+    ///
+    /// for key in 0..3 {
+    ///     let values: Vec<_> = outputs
+    ///         .iter()
+    ///         .filter(|(k, _)| *k == key)
+    ///         .map(|(_, value)| *value)
+    ///         .collect();
+    ///     let expect: Vec<_> = (0..100).filter(|value| value % 3 == key).collect();
+    ///     assert_eq!(values, expect);
+    /// }
+    /// # })
+    /// ```
+    fn par_then_keyed<K, T, P, KF, F, Fut>(self, params: P, key_fn: KF, f: F) -> ParThenKeyed<T>
+    where
+        K: 'static + Hash + Eq + Clone + Send,
+        T: 'static + Send,
+        KF: 'static + FnMut(&Self::Item) -> K + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = T> + Send,
+        P: Into<ParParams>;
+
     /// Runs an asynchronous task on parallel workers and produces items without respecting input order.
     ///
     /// The `params` sets the worker pool size and output buffer size.
@@ -251,7 +511,105 @@ where
     /// assert_eq!(doubled, expect);
     /// # })
     /// ```
-    fn par_then_unordered<T, P, F, Fut>(self, params: P, f: F) -> RecvStream<'static, T>
+    fn par_then_unordered<T, P, F, Fut>(self, params: P, f: F) -> ParThenUnordered<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = T> + Send,
+        P: Into<ParParams>;
+
+    /// Runs an asynchronous task on parallel workers and produces items without respecting
+    /// the input order, converting the panic of a task to an `Err(PanicPayload)` item.
+    fn par_then_unordered_catch_unwind<T, P, F, Fut>(
+        self,
+        params: P,
+        f: F,
+    ) -> ParThenUnorderedCatchUnwind<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = T> + Send,
+        P: Into<ParParams>;
+
+    /// Runs an asynchronous task on parallel workers and produces items respecting the input order,
+    /// converting the panic of a task to an `Err(PanicPayload)` item.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let results: Vec<_> = stream::iter(0..4)
+    ///     .par_then_catch_unwind(None, |value| async move {
+    ///         if value == 2 {
+    ///             panic!("oops");
+    ///         }
+    ///         value
+    ///     })
+    ///     .collect()
+    ///     .await;
+    ///
+    /// assert_eq!(results[0].as_ref().ok(), Some(&0));
+    /// assert_eq!(results[1].as_ref().ok(), Some(&1));
+    /// assert_eq!(results[2].as_ref().unwrap_err().message(), Some("oops"));
+    /// assert_eq!(results[3].as_ref().ok(), Some(&3));
+    /// # })
+    /// ```
+    fn par_then_catch_unwind<T, P, F, Fut>(self, params: P, f: F) -> ParThenCatchUnwind<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = T> + Send,
+        P: Into<ParParams>;
+
+    /// Runs an asynchronous task on parallel workers with a per-item timeout and produces items
+    /// respecting the input order.
+    ///
+    /// Each future is given `duration` to complete. A future that does not finish in time is
+    /// dropped and yields `Err(Elapsed)` at its position, so that a stuck future neither holds
+    /// a worker nor stalls the ordered output.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::{prelude::*, rt};
+    /// use std::time::Duration;
+    ///
+    /// let results: Vec<_> = stream::iter([10, 1000, 10])
+    ///     .par_then_timeout(None, Duration::from_millis(100), |millis| async move {
+    ///         rt::sleep(Duration::from_millis(millis)).await;
+    ///         millis
+    ///     })
+    ///     .collect()
+    ///     .await;
+    ///
+    /// assert_eq!(results[0], Ok(10));
+    /// assert!(results[1].is_err());
+    /// assert_eq!(results[2], Ok(10));
+    /// # })
+    /// ```
+    fn par_then_timeout<T, P, F, Fut>(
+        self,
+        params: P,
+        duration: Duration,
+        f: F,
+    ) -> ParThen<Result<T, Elapsed>>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = T> + Send,
+        P: Into<ParParams>;
+
+    /// Runs an asynchronous task on parallel workers with a per-item timeout and produces items
+    /// without respecting the input order.
+    ///
+    /// A future that does not finish within `duration` is dropped and yields `Err(Elapsed)`.
+    fn par_then_unordered_timeout<T, P, F, Fut>(
+        self,
+        params: P,
+        duration: Duration,
+        f: F,
+    ) -> ParThenUnordered<Result<T, Elapsed>>
     where
         T: 'static + Send,
         F: 'static + FnMut(Self::Item) -> Fut + Send,
@@ -264,6 +622,9 @@ where
     /// Each parallel worker shares the stream and executes a future for each input item.
     /// Output items are gathered to a channel and are reordered respecting to input order.
     ///
+    /// If a task panics, the [panic_policy](ParParams::panic_policy) decides whether the panic is
+    /// resumed on the consumer at the position of the item or the item is skipped.
+    ///
     /// ```rust
     /// # par_stream::rt::block_on_executor(async move {
     /// use futures::prelude::*;
@@ -317,13 +678,177 @@ where
     /// assert_eq!(doubled, expect);
     /// # })
     /// ```
-    fn par_map_unordered<T, P, F, Func>(self, params: P, f: F) -> RecvStream<'static, T>
+    fn par_map_unordered<T, P, F, Func>(self, params: P, f: F) -> ParMapUnordered<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Func + Send,
+        Func: 'static + FnOnce() -> T + Send,
+        P: Into<ParParams>;
+
+    /// Runs a blocking task on parallel workers and produces items without respecting
+    /// the input order, converting the panic of a task to an `Err(PanicPayload)` item.
+    fn par_map_unordered_catch_unwind<T, P, F, Func>(
+        self,
+        params: P,
+        f: F,
+    ) -> ParMapUnorderedCatchUnwind<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Func + Send,
+        Func: 'static + FnOnce() -> T + Send,
+        P: Into<ParParams>;
+
+    /// Runs a blocking task on parallel workers and produces items respecting the input order,
+    /// converting the panic of a task to an `Err(PanicPayload)` item.
+    fn par_map_catch_unwind<T, P, F, Func>(self, params: P, f: F) -> ParMapCatchUnwind<T>
     where
         T: 'static + Send,
         F: 'static + FnMut(Self::Item) -> Func + Send,
         Func: 'static + FnOnce() -> T + Send,
         P: Into<ParParams>;
 
+    /// Filters the items by an asynchronous predicate on parallel workers and produces items
+    /// respecting the input order.
+    ///
+    /// The predicate `f` is called on the item reference and returns a future sent to a worker.
+    /// Values needed by the future must be cloned from the item.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let evens: Vec<_> = stream::iter(0..100)
+    ///     .par_filter(None, |&value| async move { value % 2 == 0 })
+    ///     .collect()
+    ///     .await;
+    /// let expect: Vec<_> = (0..100).step_by(2).collect();
+    /// assert_eq!(evens, expect);
+    /// # })
+    /// ```
+    fn par_filter<P, F, Fut>(self, params: P, f: F) -> ParFilterMap<Self::Item>
+    where
+        F: 'static + FnMut(&Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = bool> + Send,
+        P: Into<ParParams>;
+
+    /// Filters the items by an asynchronous predicate on parallel workers and produces items
+    /// without respecting the input order.
+    fn par_filter_unordered<P, F, Fut>(self, params: P, f: F) -> ParFilterMapUnordered<Self::Item>
+    where
+        F: 'static + FnMut(&Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = bool> + Send,
+        P: Into<ParParams>;
+
+    /// Runs an asynchronous task on parallel workers, keeps the `Some` outputs and produces items
+    /// respecting the input order.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let numbers: Vec<_> = stream::iter(["1", "x", "3"])
+    ///     .par_filter_map(None, |text| async move { text.parse::<i32>().ok() })
+    ///     .collect()
+    ///     .await;
+    /// assert_eq!(numbers, [1, 3]);
+    /// # })
+    /// ```
+    fn par_filter_map<T, P, F, Fut>(self, params: P, f: F) -> ParFilterMap<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = Option<T>> + Send,
+        P: Into<ParParams>;
+
+    /// Runs an asynchronous task on parallel workers, keeps the `Some` outputs and produces items
+    /// without respecting the input order.
+    fn par_filter_map_unordered<T, P, F, Fut>(self, params: P, f: F) -> ParFilterMapUnordered<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = Option<T>> + Send,
+        P: Into<ParParams>;
+
+    /// Maps each item to a stream on parallel workers and produces the flattened items
+    /// respecting the input order.
+    ///
+    /// The stream returned by `f` is run on a worker. Its items are kept together and in order,
+    /// and the items of one input come before the items of the next input, even if an input
+    /// produces no items. The items of the earliest unfinished input are yielded as soon as
+    /// they are produced, while those of later inputs are buffered until their turn. The stream
+    /// of a later input is polled for at most [buf_size](ParParams::buf_size) items and then
+    /// waits for its turn, so that about `num_workers * buf_size` items are buffered at most.
+    /// Use [par_flat_map_iter()](ParStreamExt::par_flat_map_iter) to return an iterator.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let values: Vec<_> = stream::iter(0..4)
+    ///     .par_flat_map(None, |value| stream::iter(0..value).map(move |_| value))
+    ///     .collect()
+    ///     .await;
+    /// assert_eq!(values, [1, 2, 2, 3, 3, 3]);
+    /// # })
+    /// ```
+    fn par_flat_map<T, P, F, St>(self, params: P, f: F) -> ParFlatMap<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> St + Send,
+        St: 'static + Stream<Item = T> + Send,
+        P: Into<ParParams>;
+
+    /// Maps each item to an iterator on parallel workers and produces the flattened items
+    /// respecting the input order.
+    ///
+    /// It is the same as [par_flat_map()](ParStreamExt::par_flat_map) except that `f` returns
+    /// an iterator, which is consumed on a worker.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let values: Vec<_> = stream::iter(0..4)
+    ///     .par_flat_map_iter(None, |value| vec![value; value])
+    ///     .collect()
+    ///     .await;
+    /// assert_eq!(values, [1, 2, 2, 3, 3, 3]);
+    /// # })
+    /// ```
+    fn par_flat_map_iter<T, P, F, I>(self, params: P, f: F) -> ParFlatMap<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> I + Send,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: 'static + Send,
+        P: Into<ParParams>;
+
+    /// Maps each item to a stream on parallel workers and produces the flattened items
+    /// without respecting the input order.
+    ///
+    /// The items of the stream returned by `f` are forwarded as soon as they are produced,
+    /// without buffering the whole stream.
+    fn par_flat_map_unordered<T, P, F, St>(self, params: P, f: F) -> ParFlatMapUnordered<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> St + Send,
+        St: 'static + Stream<Item = T> + Send,
+        P: Into<ParParams>;
+
+    /// Maps each item to an iterator on parallel workers and produces the flattened items
+    /// without respecting the input order.
+    fn par_flat_map_iter_unordered<T, P, F, I>(self, params: P, f: F) -> ParFlatMapUnordered<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> I + Send,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: 'static + Send,
+        P: Into<ParParams>;
+
     /// Reduces the input stream into a single value in parallel.
     ///
     /// It maintains a parallel worker pool of `num_workers`. Each worker reduces
@@ -356,13 +881,200 @@ where
         F: 'static + FnMut(Self::Item, Self::Item) -> Fut + Send + Clone,
         Fut: 'static + Future<Output = Self::Item> + Send;
 
-    /// Runs an asynchronous task on parallel workers.
-    fn par_for_each<P, F, Fut>(self, params: P, f: F) -> BoxFuture<'static, ()>
-    where
-        F: 'static + FnMut(Self::Item) -> Fut + Send,
-        Fut: 'static + Future<Output = ()> + Send,
-        P: Into<ParParams>;
-
+    /// Reduces the input stream into a single value in parallel, respecting the input order.
+    ///
+    /// Unlike [par_reduce()](ParStreamExt::par_reduce), it works with operations that are
+    /// associative but not commutative, such as string concatenation. The input is split into
+    /// contiguous chunks of `chunk_size` items, each chunk is reduced by `fold_fn` on a worker,
+    /// and the partial values are combined in input order starting from `identity`. An empty
+    /// stream results in `identity`.
+    ///
+    /// If the workers are cancelled, the value covers only the chunks finished before
+    /// cancellation.
+    ///
+    /// # Panics
+    /// The `chunk_size` must be positive.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let text = stream::iter(0..10)
+    ///     .map(|value| value.to_string())
+    ///     .par_fold(None, 3, String::new(), |lhs, rhs| async move { lhs + &rhs })
+    ///     .await;
+    /// assert_eq!(text, "0123456789");
+    /// # })
+    /// ```
+    fn par_fold<P, F, Fut>(
+        self,
+        params: P,
+        chunk_size: usize,
+        identity: Self::Item,
+        fold_fn: F,
+    ) -> BoxFuture<'static, Self::Item>
+    where
+        P: Into<ParParams>,
+        F: 'static + FnMut(Self::Item, Self::Item) -> Fut + Send + Clone,
+        Fut: 'static + Future<Output = Self::Item> + Send;
+
+    /// Folds the input items into an accumulator per key in parallel.
+    ///
+    /// Each of `num_workers` workers folds the items it takes into its own accumulators,
+    /// keyed by `key_fn`. A missing accumulator is created by `init`. Once the input ends, the
+    /// partial accumulators of the same key are merged by `merge_fn` in parallel. The output
+    /// map is empty if the workers are cancelled.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let words = vec!["apple", "bird", "avocado", "cat", "banana"];
+    ///
+    /// // count the words by the initial letter
+    /// let counts = stream::iter(words)
+    ///     .par_fold_by_key(
+    ///         None,
+    ///         |word| word.chars().next().unwrap(),
+    ///         || 0,
+    ///         |count, _word| async move { count + 1 },
+    ///         |lhs, rhs| async move { lhs + rhs },
+    ///     )
+    ///     .await;
+    ///
+    /// assert_eq!(counts[&'a'], 2);
+    /// assert_eq!(counts[&'b'], 2);
+    /// assert_eq!(counts[&'c'], 1);
+    /// # })
+    /// ```
+    fn par_fold_by_key<K, Acc, P, KF, InitF, FoldF, FoldFut, MergeF, MergeFut>(
+        self,
+        params: P,
+        key_fn: KF,
+        init: InitF,
+        fold_fn: FoldF,
+        merge_fn: MergeF,
+    ) -> BoxFuture<'static, HashMap<K, Acc>>
+    where
+        K: 'static + Send + Hash + Eq,
+        Acc: 'static + Send,
+        KF: 'static + FnMut(&Self::Item) -> K + Send + Clone,
+        InitF: 'static + FnMut() -> Acc + Send + Clone,
+        FoldF: 'static + FnMut(Acc, Self::Item) -> FoldFut + Send + Clone,
+        FoldFut: 'static + Future<Output = Acc> + Send,
+        MergeF: 'static + FnMut(Acc, Acc) -> MergeFut + Send + Clone,
+        MergeFut: 'static + Future<Output = Acc> + Send,
+        P: Into<ParParams>;
+
+    /// Sorts the input items in parallel and produces the items in sorted order.
+    ///
+    /// The input is split into chunks of `chunk_size` items, which are sorted by `cmp` on
+    /// parallel workers. The sorted chunks are then k-way merged on a blocking thread.
+    /// The sort is stable. All input items are kept in memory until the input ends. Use
+    /// [par_sort_by_spilled()](ParStreamExt::par_sort_by_spilled) to keep the sorted chunks
+    /// on disk instead.
+    ///
+    /// # Panics
+    /// The `chunk_size` must be positive.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let sorted: Vec<_> = stream::iter([5, 1, 4, 2, 3])
+    ///     .par_sort_by(None, 2, |lhs, rhs| rhs.cmp(lhs))
+    ///     .collect()
+    ///     .await;
+    /// assert_eq!(sorted, [5, 4, 3, 2, 1]);
+    /// # })
+    /// ```
+    fn par_sort_by<P, F>(
+        self,
+        params: P,
+        chunk_size: usize,
+        cmp: F,
+    ) -> BoxStream<'static, Self::Item>
+    where
+        P: Into<ParParams>,
+        F: 'static + Fn(&Self::Item, &Self::Item) -> cmp::Ordering + Send + Clone;
+
+    /// Sorts the input items by the key computed by `key_fn` in parallel.
+    ///
+    /// It works like [par_sort_by()](ParStreamExt::par_sort_by).
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let sorted: Vec<_> = stream::iter(["ccc", "a", "bb"])
+    ///     .par_sort_by_key(None, 2, |text| text.len())
+    ///     .collect()
+    ///     .await;
+    /// assert_eq!(sorted, ["a", "bb", "ccc"]);
+    /// # })
+    /// ```
+    fn par_sort_by_key<P, K, F>(
+        self,
+        params: P,
+        chunk_size: usize,
+        key_fn: F,
+    ) -> BoxStream<'static, Self::Item>
+    where
+        P: Into<ParParams>,
+        K: Ord,
+        F: 'static + Fn(&Self::Item) -> K + Send + Clone;
+
+    /// Sorts the input items in parallel, spilling the sorted chunks to files in `dir`.
+    ///
+    /// It works like [par_sort_by()](ParStreamExt::par_sort_by), except that each sorted
+    /// chunk is written to a run file by the worker, and the runs are merged by reading the files
+    /// back. At most about `chunk_size` items per worker are held in memory, so it can sort
+    /// datasets larger than memory. The items are encoded by the [Spill] trait. At most 64 run
+    /// files are merged at a time, and more runs are merged in several passes. The run files
+    /// are removed once the output stream is dropped.
+    ///
+    /// An I/O error is yielded as an `Err` item and ends the stream.
+    ///
+    /// # Panics
+    /// The `chunk_size` must be positive.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let sorted: Vec<u64> = stream::iter([5u64, 1, 4, 2, 3])
+    ///     .par_sort_by_spilled(None, 2, std::env::temp_dir(), |lhs, rhs| lhs.cmp(rhs))
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(sorted, [1, 2, 3, 4, 5]);
+    /// # })
+    /// ```
+    fn par_sort_by_spilled<P, D, F>(
+        self,
+        params: P,
+        chunk_size: usize,
+        dir: D,
+        cmp: F,
+    ) -> BoxStream<'static, io::Result<Self::Item>>
+    where
+        Self::Item: Spill,
+        P: Into<ParParams>,
+        D: Into<PathBuf>,
+        F: 'static + Fn(&Self::Item, &Self::Item) -> cmp::Ordering + Send + Clone;
+
+    /// Runs an asynchronous task on parallel workers.
+    fn par_for_each<P, F, Fut>(self, params: P, f: F) -> BoxFuture<'static, ()>
+    where
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = ()> + Send,
+        P: Into<ParParams>;
+
     /// Runs a blocking task on parallel workers.
     fn par_for_each_blocking<P, F, Func>(self, params: P, f: F) -> BoxFuture<'static, ()>
     where
@@ -377,16 +1089,27 @@ where
     S::Item: 'static + Send,
 {
     fn spawned<B>(self, buf_size: B) -> RecvStream<'static, Self::Item>
+    where
+        B: Into<BufSize>,
+    {
+        spawned_on(None, buf_size, self)
+    }
+
+    fn spawned_abortable<B>(self, buf_size: B) -> Spawned<Self::Item>
     where
         B: Into<BufSize>,
     {
         let (tx, rx) = utils::channel(buf_size.into().get());
 
-        rt::spawn(async move {
+        let task = rt::spawn(async move {
             let _ = self.map(Ok).forward(tx.into_sink()).await;
-        });
+        })
+        .abort_on_drop();
 
-        rx.into_stream()
+        Spawned {
+            stream: rx.into_stream(),
+            _task: Arc::new(Mutex::new(task)),
+        }
     }
 
     fn map_blocking<B, T, F>(self, buf_size: B, mut f: F) -> RecvStream<'static, T>
@@ -425,27 +1148,41 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
 
         let (input_tx, input_rx) = utils::channel(buf_size);
         let (output_tx, output_rx) = utils::channel(buf_size);
 
-        rt::spawn(async move {
-            let _ = self.map(Ok).forward(input_tx.into_sink()).await;
+        rt::spawn_on(runtime.as_ref(), {
+            let cancelled = cancel.cancelled();
+
+            async move {
+                let _ = self
+                    .take_until(cancelled)
+                    .map(Ok)
+                    .forward(input_tx.into_sink())
+                    .await;
+            }
         });
 
         (0..num_workers).for_each(move |worker_index| {
             let output_tx = output_tx.clone();
             let f = f.clone();
             let input_rx = input_rx.clone();
+            let cancelled = cancel.cancelled();
 
-            rt::spawn(async move {
+            rt::spawn_on(runtime.as_ref(), async move {
                 let _ = stream::repeat(())
                     .stateful_then((input_rx, f), |(input_rx, mut f), ()| async move {
                         f(worker_index, input_rx)
                             .await
                             .map(move |(item, input_rx)| ((input_rx, f), item))
                     })
+                    .take_until(cancelled)
                     .map(Ok)
                     .forward(output_tx.into_sink())
                     .await;
@@ -455,6 +1192,210 @@ where
         output_rx.into_stream()
     }
 
+    fn par_then_sharded<K, T, P, KF, F, Fut>(
+        self,
+        params: P,
+        mut key_fn: KF,
+        f: F,
+    ) -> ParThenSharded<T>
+    where
+        K: Hash,
+        T: 'static + Send,
+        KF: 'static + FnMut(&Self::Item) -> K + Send,
+        F: 'static + Send + Clone + FnMut(usize, Self::Item) -> Fut,
+        Fut: 'static + Future<Output = T> + Send,
+        P: Into<ParParams>,
+    {
+        let ParParams {
+            num_workers,
+            buf_size,
+            cancel,
+            panic_policy,
+            runtime,
+            ..
+        } = params.into();
+        assert!(num_workers > 0, "num_workers must be positive");
+        let cancel = cancel.unwrap_or_default();
+        let (output_tx, output_rx) = utils::channel(buf_size);
+
+        let mut builder = self
+            .take_until(cancel.cancelled())
+            .pull_routing(buf_size, move |item| {
+                let mut hasher = hash_map::DefaultHasher::new();
+                key_fn(item).hash(&mut hasher);
+                (hasher.finish() % num_workers as u64) as usize
+            });
+        let receivers: Vec<_> = (0..num_workers)
+            .map(|worker_index| builder.register(worker_index).unwrap())
+            .collect();
+        builder.build();
+
+        receivers
+            .into_iter()
+            .enumerate()
+            .for_each(move |(worker_index, input_rx)| {
+                let output_tx = output_tx.clone();
+                let mut f = f.clone();
+                let cancelled = cancel.cancelled();
+
+                rt::spawn_on(runtime.as_ref(), async move {
+                    let _ = input_rx
+                        .then(move |item| {
+                            let fut = panic::catch_unwind(|| f(worker_index, item));
+                            panic::catch_unwind_future(fut)
+                        })
+                        .take_until(cancelled)
+                        .map(Ok)
+                        .forward(output_tx.into_sink())
+                        .await;
+                });
+            });
+
+        ResumeUnwind::new(output_rx.into_stream(), panic_policy)
+    }
+
+    fn par_then_init<T, P, State, InitF, F, Fut>(self, params: P, init: InitF, f: F) -> ParThen<T>
+    where
+        T: 'static + Send,
+        State: 'static + Send,
+        InitF: 'static + Send + Clone + FnMut(usize) -> State,
+        F: 'static + Send + Clone + FnMut(State, Self::Item) -> Fut,
+        Fut: 'static + Future<Output = (T, State)> + Send,
+        P: Into<ParParams>,
+    {
+        let ParParams {
+            num_workers,
+            buf_size,
+            cancel,
+            panic_policy,
+            reorder_capacity,
+            runtime,
+            ..
+        } = params.into();
+        let cancel = cancel.unwrap_or_default();
+        let (output_tx, output_rx) = utils::channel(buf_size);
+        let input = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            reordered::enumerate_with_permits(self, reorder_capacity)
+                .take_until(cancel.cancelled()),
+        );
+
+        (0..num_workers).for_each(|worker_index| {
+            let input = input.clone();
+            let output_tx = output_tx.clone();
+            let cancelled = cancel.cancelled();
+            let state = (None, init.clone(), f.clone());
+
+            rt::spawn_on(runtime.as_ref(), async move {
+                let _ = input
+                    .stateful_then(
+                        state,
+                        move |(state, mut init, mut f), (index, permit, item)| {
+                            async move {
+                                let state = match state {
+                                    Some(state) => Ok(state),
+                                    None => panic::catch_unwind(|| init(worker_index)),
+                                };
+                                let result = match state {
+                                    Ok(state) => {
+                                        let fut = panic::catch_unwind(|| f(state, item));
+                                        panic::catch_unwind_future(fut).await
+                                    }
+                                    Err(payload) => Err(payload),
+                                };
+
+                                // the state is lost if the task panics
+                                let (state, output) = match result {
+                                    Ok((output, state)) => (Some(state), Ok(output)),
+                                    Err(payload) => (None, Err(payload)),
+                                };
+                                Some(((state, init, f), (index, (permit, output))))
+                            }
+                        },
+                    )
+                    .take_until(cancelled)
+                    .map(Ok)
+                    .forward(output_tx.into_sink())
+                    .await;
+            });
+        });
+
+        let stream = output_rx
+            .into_stream()
+            .boxed()
+            .reorder_enumerated_dense()
+            .take_until(cancel.cancelled());
+        ResumeUnwind::new(Reordered { stream }, panic_policy)
+    }
+
+    fn par_map_init<T, P, State, InitF, F>(self, params: P, init: InitF, f: F) -> ParMap<T>
+    where
+        T: 'static + Send,
+        State: 'static,
+        InitF: 'static + Send + Clone + FnMut(usize) -> State,
+        F: 'static + Send + Clone + FnMut(&mut State, Self::Item) -> T,
+        P: Into<ParParams>,
+    {
+        let ParParams {
+            num_workers,
+            buf_size,
+            cancel,
+            panic_policy,
+            reorder_capacity,
+            runtime,
+            ..
+        } = params.into();
+        let cancel = cancel.unwrap_or_default();
+        let (output_tx, output_rx) = utils::channel(buf_size);
+        let input = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            reordered::enumerate_with_permits(self, reorder_capacity)
+                .take_until(cancel.cancelled()),
+        );
+
+        (0..num_workers).for_each(|worker_index| {
+            let mut input = input.clone();
+            let output_tx = output_tx.clone();
+            let cancel = cancel.clone();
+            let mut init = init.clone();
+            let mut f = f.clone();
+
+            rt::spawn_blocking_on(runtime.as_ref(), move || {
+                let mut state = None;
+
+                while let Some((index, permit, item)) = rt::block_on(input.next()) {
+                    if cancel.is_cancelled() {
+                        break;
+                    }
+
+                    let output = panic::catch_unwind(|| {
+                        let state = state.get_or_insert_with(|| init(worker_index));
+                        f(state, item)
+                    });
+
+                    // discard the state that may be broken by the panic
+                    if output.is_err() {
+                        state = None;
+                    }
+
+                    let result = output_tx.send((index, (permit, output)));
+                    if result.is_err() {
+                        break;
+                    }
+                }
+            });
+        });
+
+        let stream = output_rx
+            .into_stream()
+            .boxed()
+            .reorder_enumerated_dense()
+            .take_until(cancel.cancelled());
+        ResumeUnwind::new(Reordered { stream }, panic_policy)
+    }
+
     fn pull_routing<B, K, Q, F>(self, buf_size: B, key_fn: F) -> PullBuilder<Self, K, F, Q>
     where
         Self: 'static + Send + Stream,
@@ -483,24 +1424,135 @@ where
         BroadcastBuilder::new(self, buf_size, send_all)
     }
 
-    fn par_then<T, P, F, Fut>(self, params: P, mut f: F) -> ParThen<T>
+    fn par_then<T, P, F, Fut>(self, params: P, f: F) -> ParThen<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = T> + Send,
+        P: Into<ParParams>,
+    {
+        let params = params.into();
+        let panic_policy = params.panic_policy;
+        ResumeUnwind::new(self.par_then_catch_unwind(params, f), panic_policy)
+    }
+
+    fn par_then_catch_unwind<T, P, F, Fut>(self, params: P, mut f: F) -> ParThenCatchUnwind<T>
     where
         T: 'static + Send,
         F: 'static + FnMut(Self::Item) -> Fut + Send,
         Fut: 'static + Future<Output = T> + Send,
         P: Into<ParParams>,
     {
-        let indexed_f = move |(index, item)| {
-            let fut = f(item);
-            fut.map(move |output| (index, output))
+        let params = params.into();
+        let cancelled = params.cancel.clone().unwrap_or_default().cancelled();
+        let capacity = params.reorder_capacity;
+        let indexed_f = move |(index, permit, item)| {
+            let fut = panic::catch_unwind(|| f(item));
+            panic::catch_unwind_future(fut).map(move |output| (index, (permit, output)))
         };
 
-        self.enumerate()
+        let stream = reordered::enumerate_with_permits(self, capacity)
             .par_then_unordered(params, indexed_f)
-            .reorder_enumerated()
+            .boxed()
+            .reorder_enumerated_dense()
+            .take_until(cancelled);
+        Reordered { stream }
+    }
+
+    fn par_then_keyed<K, T, P, KF, F, Fut>(
+        self,
+        params: P,
+        mut key_fn: KF,
+        mut f: F,
+    ) -> ParThenKeyed<T>
+    where
+        K: 'static + Hash + Eq + Clone + Send,
+        T: 'static + Send,
+        KF: 'static + FnMut(&Self::Item) -> K + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = T> + Send,
+        P: Into<ParParams>,
+    {
+        let params = params.into();
+        let panic_policy = params.panic_policy;
+        let cancelled = params.cancel.clone().unwrap_or_default().cancelled();
+        let counters: KeyCounters<K> = Arc::default();
+        let input_counters = counters.clone();
+
+        // number the items of each key separately, restarting from zero once the reordering
+        // side forgets the key
+        let indexed = self.map(move |item| {
+            let key = key_fn(&item);
+            let mut counters = input_counters.lock().unwrap();
+            let counter = counters.entry(key.clone()).or_insert(0);
+            let index = *counter;
+            *counter += 1;
+            (index, key, item)
+        });
+        let indexed_f = move |(index, key, item)| {
+            let fut = panic::catch_unwind(|| f(item));
+            panic::catch_unwind_future(fut).map(move |output| (index, (key, output)))
+        };
+
+        let outputs = indexed.par_then_unordered(params, indexed_f);
+        let output_key = |(key, _): &(K, _)| key.clone();
+        let stream = ReorderByKey::with_counters(outputs, output_key, counters)
+            .map(|(_key, output)| output)
+            .take_until(cancelled)
+            .boxed();
+        ResumeUnwind::new(stream, panic_policy)
+    }
+
+    fn par_then_timeout<T, P, F, Fut>(
+        self,
+        params: P,
+        duration: Duration,
+        mut f: F,
+    ) -> ParThen<Result<T, Elapsed>>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = T> + Send,
+        P: Into<ParParams>,
+    {
+        self.par_then(params, move |item| rt::timeout(duration, f(item)))
+    }
+
+    fn par_then_unordered_timeout<T, P, F, Fut>(
+        self,
+        params: P,
+        duration: Duration,
+        mut f: F,
+    ) -> ParThenUnordered<Result<T, Elapsed>>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = T> + Send,
+        P: Into<ParParams>,
+    {
+        self.par_then_unordered(params, move |item| rt::timeout(duration, f(item)))
+    }
+
+    fn par_then_unordered<T, P, F, Fut>(self, params: P, f: F) -> ParThenUnordered<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = T> + Send,
+        P: Into<ParParams>,
+    {
+        let params = params.into();
+        let panic_policy = params.panic_policy;
+        ResumeUnwind::new(
+            self.par_then_unordered_catch_unwind(params, f),
+            panic_policy,
+        )
     }
 
-    fn par_then_unordered<T, P, F, Fut>(self, params: P, f: F) -> RecvStream<'static, T>
+    fn par_then_unordered_catch_unwind<T, P, F, Fut>(
+        self,
+        params: P,
+        f: F,
+    ) -> ParThenUnorderedCatchUnwind<T>
     where
         T: 'static + Send,
         F: 'static + FnMut(Self::Item) -> Fut + Send,
@@ -510,22 +1562,31 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
         let (output_tx, output_rx) = utils::channel(buf_size);
-        let stream = self
-            .stateful_map(f, |mut f, item| {
-                let fut = f(item);
-                Some((f, fut))
-            })
-            .spawned(buf_size);
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            self.take_until(cancel.cancelled())
+                .stateful_map(f, |mut f, item| {
+                    let fut = panic::catch_unwind(|| f(item));
+                    Some((f, panic::catch_unwind_future(fut)))
+                }),
+        );
 
         (0..num_workers).for_each(move |_| {
             let stream = stream.clone();
             let output_tx = output_tx.clone();
+            let cancelled = cancel.cancelled();
 
-            rt::spawn(async move {
+            rt::spawn_on(runtime.as_ref(), async move {
                 let _ = stream
                     .then(|fut| fut)
+                    .take_until(cancelled)
                     .map(Ok)
                     .forward(output_tx.into_sink())
                     .await;
@@ -534,22 +1595,58 @@ where
         output_rx.into_stream()
     }
 
-    fn par_map<T, P, F, Func>(self, params: P, mut f: F) -> ParMap<T>
+    fn par_map<T, P, F, Func>(self, params: P, f: F) -> ParMap<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Func + Send,
+        Func: 'static + FnOnce() -> T + Send,
+        P: Into<ParParams>,
+    {
+        let params = params.into();
+        let panic_policy = params.panic_policy;
+        ResumeUnwind::new(self.par_map_catch_unwind(params, f), panic_policy)
+    }
+
+    fn par_map_catch_unwind<T, P, F, Func>(self, params: P, mut f: F) -> ParMapCatchUnwind<T>
     where
         T: 'static + Send,
         F: 'static + FnMut(Self::Item) -> Func + Send,
         Func: 'static + FnOnce() -> T + Send,
         P: Into<ParParams>,
     {
-        self.enumerate()
-            .par_map_unordered(params, move |(index, item)| {
-                let job = f(item);
-                move || (index, job())
+        let params = params.into();
+        let cancelled = params.cancel.clone().unwrap_or_default().cancelled();
+
+        let capacity = params.reorder_capacity;
+
+        let stream = reordered::enumerate_with_permits(self, capacity)
+            .par_map_unordered(params, move |(index, permit, item)| {
+                let job = panic::catch_unwind(|| f(item));
+                move || (index, (permit, job.and_then(panic::catch_unwind)))
             })
-            .reorder_enumerated()
+            .boxed()
+            .reorder_enumerated_dense()
+            .take_until(cancelled);
+        Reordered { stream }
     }
 
-    fn par_map_unordered<T, P, F, Func>(self, params: P, f: F) -> RecvStream<'static, T>
+    fn par_map_unordered<T, P, F, Func>(self, params: P, f: F) -> ParMapUnordered<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Func + Send,
+        Func: 'static + FnOnce() -> T + Send,
+        P: Into<ParParams>,
+    {
+        let params = params.into();
+        let panic_policy = params.panic_policy;
+        ResumeUnwind::new(self.par_map_unordered_catch_unwind(params, f), panic_policy)
+    }
+
+    fn par_map_unordered_catch_unwind<T, P, F, Func>(
+        self,
+        params: P,
+        f: F,
+    ) -> ParMapUnorderedCatchUnwind<T>
     where
         T: 'static + Send,
         F: 'static + FnMut(Self::Item) -> Func + Send,
@@ -559,23 +1656,34 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
-        let stream = self
-            .stateful_map(f, |mut f, item| {
-                let func = f(item);
-                Some((f, func))
-            })
-            .spawned(buf_size);
+        let cancel = cancel.unwrap_or_default();
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            self.take_until(cancel.cancelled())
+                .stateful_map(f, |mut f, item| {
+                    let func = panic::catch_unwind(|| f(item));
+                    Some((f, func))
+                }),
+        );
         let (output_tx, output_rx) = utils::channel(buf_size);
 
         (0..num_workers).for_each(move |_| {
             let mut stream = stream.clone();
             let output_tx = output_tx.clone();
+            let cancel = cancel.clone();
 
-            rt::spawn_blocking(move || {
+            rt::spawn_blocking_on(runtime.as_ref(), move || {
                 while let Some(job) = rt::block_on(stream.next()) {
-                    let output = job();
-                    let result = output_tx.send(output);
+                    if cancel.is_cancelled() {
+                        break;
+                    }
+
+                    let result = output_tx.send(job.and_then(panic::catch_unwind));
                     if result.is_err() {
                         break;
                     }
@@ -586,7 +1694,203 @@ where
         output_rx.into_stream()
     }
 
-    fn par_reduce<P, F, Fut>(
+    fn par_filter<P, F, Fut>(self, params: P, mut f: F) -> ParFilterMap<Self::Item>
+    where
+        F: 'static + FnMut(&Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = bool> + Send,
+        P: Into<ParParams>,
+    {
+        self.par_filter_map(params, move |item| {
+            let fut = f(&item);
+            async move { fut.await.then_some(item) }
+        })
+    }
+
+    fn par_filter_unordered<P, F, Fut>(
+        self,
+        params: P,
+        mut f: F,
+    ) -> ParFilterMapUnordered<Self::Item>
+    where
+        F: 'static + FnMut(&Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = bool> + Send,
+        P: Into<ParParams>,
+    {
+        self.par_filter_map_unordered(params, move |item| {
+            let fut = f(&item);
+            async move { fut.await.then_some(item) }
+        })
+    }
+
+    fn par_filter_map<T, P, F, Fut>(self, params: P, f: F) -> ParFilterMap<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = Option<T>> + Send,
+        P: Into<ParParams>,
+    {
+        // the `None` outputs still pass the reordering to keep the indices contiguous
+        self.par_then(params, f)
+            .filter_map(future::ready as fn(Option<T>) -> future::Ready<Option<T>>)
+    }
+
+    fn par_filter_map_unordered<T, P, F, Fut>(self, params: P, f: F) -> ParFilterMapUnordered<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> Fut + Send,
+        Fut: 'static + Future<Output = Option<T>> + Send,
+        P: Into<ParParams>,
+    {
+        self.par_then_unordered(params, f)
+            .filter_map(future::ready as fn(Option<T>) -> future::Ready<Option<T>>)
+    }
+
+    fn par_flat_map<T, P, F, St>(self, params: P, f: F) -> ParFlatMap<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> St + Send,
+        St: 'static + Stream<Item = T> + Send,
+        P: Into<ParParams>,
+    {
+        let ParParams {
+            num_workers,
+            buf_size,
+            cancel,
+            panic_policy,
+            reorder_capacity,
+            runtime,
+            ..
+        } = params.into();
+        let cancel = cancel.unwrap_or_default();
+        let (output_tx, output_rx) = utils::channel(buf_size);
+        let (commit_tx, commit_rx) = watch::channel(0);
+        let input = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            reordered::enumerate_with_permits(self, reorder_capacity)
+                .take_until(cancel.cancelled())
+                .stateful_map(f, |mut f, (index, permit, item)| {
+                    let stream = panic::catch_unwind(|| f(item));
+                    Some((f, (index, permit, stream)))
+                }),
+        );
+
+        let stream = Flattened::new(
+            output_rx.into_stream().take_until(cancel.cancelled()),
+            commit_tx,
+        );
+
+        (0..num_workers).for_each(move |_| {
+            let input = input.clone();
+            let output_tx = output_tx.clone();
+            let commit_rx = commit_rx.clone();
+            let cancelled = cancel.cancelled();
+
+            rt::spawn_on(runtime.as_ref(), async move {
+                let _ = input
+                    .flat_map(move |(index, permit, stream)| {
+                        let commit_rx = commit_rx.clone();
+
+                        // the items are tagged with the sub-index, and the last mark hands
+                        // the permit over to the consumer
+                        panic::catch_unwind_stream(stream)
+                            .enumerate()
+                            .then(move |(sub_index, item)| {
+                                let mut commit_rx = commit_rx.clone();
+
+                                async move {
+                                    // a later input stops polling its stream once it is
+                                    // buf_size items ahead until its turn comes
+                                    if buf_size.is_some_and(|size| sub_index >= size.max(1)) {
+                                        while *commit_rx.borrow() < index {
+                                            if commit_rx.changed().await.is_err() {
+                                                break;
+                                            }
+                                        }
+                                    }
+                                    Tagged::Item(index, sub_index, item)
+                                }
+                            })
+                            .chain(stream::once(async move { Tagged::Last(index, permit) }))
+                    })
+                    .take_until(cancelled)
+                    .map(Ok)
+                    .forward(output_tx.into_sink())
+                    .await;
+            });
+        });
+
+        ResumeUnwind::new(stream, panic_policy)
+    }
+
+    fn par_flat_map_iter<T, P, F, I>(self, params: P, mut f: F) -> ParFlatMap<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> I + Send,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: 'static + Send,
+        P: Into<ParParams>,
+    {
+        self.par_flat_map(params, move |item| stream::iter(f(item)))
+    }
+
+    fn par_flat_map_unordered<T, P, F, St>(self, params: P, f: F) -> ParFlatMapUnordered<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> St + Send,
+        St: 'static + Stream<Item = T> + Send,
+        P: Into<ParParams>,
+    {
+        let ParParams {
+            num_workers,
+            buf_size,
+            cancel,
+            panic_policy,
+            runtime,
+            ..
+        } = params.into();
+        let cancel = cancel.unwrap_or_default();
+        let (output_tx, output_rx) = utils::channel(buf_size);
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            self.take_until(cancel.cancelled())
+                .stateful_map(f, |mut f, item| {
+                    let stream = panic::catch_unwind(|| f(item));
+                    Some((f, stream))
+                }),
+        );
+
+        (0..num_workers).for_each(move |_| {
+            let stream = stream.clone();
+            let output_tx = output_tx.clone();
+            let cancelled = cancel.cancelled();
+
+            rt::spawn_on(runtime.as_ref(), async move {
+                let _ = stream
+                    .flat_map(panic::catch_unwind_stream)
+                    .take_until(cancelled)
+                    .map(Ok)
+                    .forward(output_tx.into_sink())
+                    .await;
+            });
+        });
+
+        ResumeUnwind::new(output_rx.into_stream(), panic_policy)
+    }
+
+    fn par_flat_map_iter_unordered<T, P, F, I>(self, params: P, mut f: F) -> ParFlatMapUnordered<T>
+    where
+        T: 'static + Send,
+        F: 'static + FnMut(Self::Item) -> I + Send,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: 'static + Send,
+        P: Into<ParParams>,
+    {
+        self.par_flat_map_unordered(params, move |item| stream::iter(f(item)))
+    }
+
+    fn par_reduce<P, F, Fut>(
         self,
         params: P,
         reduce_fn: F,
@@ -599,18 +1903,30 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
-        let stream = self.spawned(buf_size);
+        let cancel = cancel.unwrap_or_default();
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            self.take_until(cancel.cancelled()),
+        );
 
         // phase 1
         let phase_1_future = {
             let reduce_fn = reduce_fn.clone();
+            let runtime = runtime.clone();
             async move {
                 let reducer_futures = (0..num_workers).map(move |_| {
                     let reduce_fn = reduce_fn.clone();
                     let stream = stream.clone();
 
-                    rt::spawn(async move { stream.reduce(reduce_fn).await })
+                    rt::spawn_on(
+                        runtime.as_ref(),
+                        async move { stream.reduce(reduce_fn).await },
+                    )
                 });
 
                 future::join_all(reducer_futures).await
@@ -631,7 +1947,7 @@ where
                 count += 1;
             }
 
-            let pairing_future = rt::spawn(async move {
+            let pairing_future = rt::spawn_on(runtime.as_ref(), async move {
                 while count >= 2 {
                     let first = feedback_rx.recv_async().await.unwrap();
                     let second = feedback_rx.recv_async().await.unwrap();
@@ -654,7 +1970,7 @@ where
                 let feedback_tx = feedback_tx.clone();
                 let mut reduce_fn = reduce_fn.clone();
 
-                rt::spawn(async move {
+                rt::spawn_on(runtime.as_ref(), async move {
                     while let Ok((first, second)) = pair_rx.recv_async().await {
                         let reduced = reduce_fn(first, second).await;
                         feedback_tx
@@ -668,12 +1984,244 @@ where
 
             let (output, _) = join!(pairing_future, future::join_all(worker_futures));
 
-            output
+            // discard the partially reduced value if the workers are cancelled
+            if cancel.is_cancelled() {
+                None
+            } else {
+                output
+            }
         };
 
         phase_2_future.boxed()
     }
 
+    fn par_fold<P, F, Fut>(
+        self,
+        params: P,
+        chunk_size: usize,
+        identity: Self::Item,
+        fold_fn: F,
+    ) -> BoxFuture<'static, Self::Item>
+    where
+        P: Into<ParParams>,
+        F: 'static + FnMut(Self::Item, Self::Item) -> Fut + Send + Clone,
+        Fut: 'static + Future<Output = Self::Item> + Send,
+    {
+        assert!(chunk_size > 0, "chunk_size must be positive");
+        let combine_fn = fold_fn.clone();
+
+        // reduce contiguous chunks in parallel
+        let partials = self.chunks(chunk_size).par_then(params, move |chunk| {
+            let mut fold_fn = fold_fn.clone();
+
+            async move {
+                let mut items = chunk.into_iter();
+                let first = items.next().unwrap();
+                stream::iter(items).fold(first, &mut fold_fn).await
+            }
+        });
+
+        // combine the partial values in input order
+        partials.fold(identity, combine_fn).boxed()
+    }
+
+    fn par_fold_by_key<K, Acc, P, KF, InitF, FoldF, FoldFut, MergeF, MergeFut>(
+        self,
+        params: P,
+        key_fn: KF,
+        init: InitF,
+        fold_fn: FoldF,
+        merge_fn: MergeF,
+    ) -> BoxFuture<'static, HashMap<K, Acc>>
+    where
+        K: 'static + Send + Hash + Eq,
+        Acc: 'static + Send,
+        KF: 'static + FnMut(&Self::Item) -> K + Send + Clone,
+        InitF: 'static + FnMut() -> Acc + Send + Clone,
+        FoldF: 'static + FnMut(Acc, Self::Item) -> FoldFut + Send + Clone,
+        FoldFut: 'static + Future<Output = Acc> + Send,
+        MergeF: 'static + FnMut(Acc, Acc) -> MergeFut + Send + Clone,
+        MergeFut: 'static + Future<Output = Acc> + Send,
+        P: Into<ParParams>,
+    {
+        let params = params.into();
+        let ParParams {
+            num_workers,
+            buf_size,
+            ref cancel,
+            ref runtime,
+            ..
+        } = params;
+        let cancel = cancel.clone().unwrap_or_default();
+        let runtime = runtime.clone();
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            self.take_until(cancel.cancelled()),
+        );
+
+        // phase 1
+        let phase_1_future = {
+            let folder_futures = (0..num_workers).map(move |_| {
+                let mut stream = stream.clone();
+                let mut key_fn = key_fn.clone();
+                let mut init = init.clone();
+                let mut fold_fn = fold_fn.clone();
+
+                rt::spawn_on(runtime.as_ref(), async move {
+                    let mut accs = HashMap::new();
+
+                    while let Some(item) = stream.next().await {
+                        let key = key_fn(&item);
+                        let acc = accs.remove(&key).unwrap_or_else(&mut init);
+                        let acc = fold_fn(acc, item).await;
+                        accs.insert(key, acc);
+                    }
+
+                    accs
+                })
+            });
+
+            future::join_all(folder_futures)
+        };
+
+        // phase 2
+        let phase_2_future = async move {
+            let mut groups: HashMap<K, Vec<Acc>> = HashMap::new();
+
+            for (key, acc) in phase_1_future.await.into_iter().flatten() {
+                groups.entry(key).or_default().push(acc);
+            }
+
+            let output: HashMap<_, _> = stream::iter(groups)
+                .par_then_unordered(params, move |(key, accs)| {
+                    let mut merge_fn = merge_fn.clone();
+
+                    async move {
+                        let mut accs = accs.into_iter();
+                        let first = accs.next().unwrap();
+                        let merged = stream::iter(accs).fold(first, &mut merge_fn).await;
+                        (key, merged)
+                    }
+                })
+                .collect()
+                .await;
+
+            // discard the partially folded values if the workers are cancelled
+            if cancel.is_cancelled() {
+                HashMap::new()
+            } else {
+                output
+            }
+        };
+
+        phase_2_future.boxed()
+    }
+
+    fn par_sort_by<P, F>(
+        self,
+        params: P,
+        chunk_size: usize,
+        cmp: F,
+    ) -> BoxStream<'static, Self::Item>
+    where
+        P: Into<ParParams>,
+        F: 'static + Fn(&Self::Item, &Self::Item) -> cmp::Ordering + Send + Clone,
+    {
+        assert!(chunk_size > 0, "chunk_size must be positive");
+        let params = params.into();
+        let buf_size = params.buf_size;
+        let runtime = params.runtime.clone();
+        let sort_cmp = cmp.clone();
+
+        // sort chunks in parallel
+        let runs = self.chunks(chunk_size).par_map(params, move |mut chunk| {
+            let cmp = sort_cmp.clone();
+
+            move || {
+                chunk.sort_by(cmp);
+                chunk
+            }
+        });
+
+        // merge sorted chunks
+        runs.collect::<Vec<_>>()
+            .map(move |runs| {
+                let sources: Vec<_> = runs
+                    .into_iter()
+                    .map(|run| run.into_iter().map(Ok))
+                    .collect();
+                let merge = KMerge::new(sources, cmp).flatten();
+                iter_blocking_on(runtime.as_ref(), buf_size, merge)
+            })
+            .flatten_stream()
+            .boxed()
+    }
+
+    fn par_sort_by_key<P, K, F>(
+        self,
+        params: P,
+        chunk_size: usize,
+        key_fn: F,
+    ) -> BoxStream<'static, Self::Item>
+    where
+        P: Into<ParParams>,
+        K: Ord,
+        F: 'static + Fn(&Self::Item) -> K + Send + Clone,
+    {
+        self.par_sort_by(params, chunk_size, move |lhs, rhs| {
+            key_fn(lhs).cmp(&key_fn(rhs))
+        })
+    }
+
+    fn par_sort_by_spilled<P, D, F>(
+        self,
+        params: P,
+        chunk_size: usize,
+        dir: D,
+        cmp: F,
+    ) -> BoxStream<'static, io::Result<Self::Item>>
+    where
+        Self::Item: Spill,
+        P: Into<ParParams>,
+        D: Into<PathBuf>,
+        F: 'static + Fn(&Self::Item, &Self::Item) -> cmp::Ordering + Send + Clone,
+    {
+        assert!(chunk_size > 0, "chunk_size must be positive");
+        let params = params.into();
+        let buf_size = params.buf_size;
+        let runtime = params.runtime.clone();
+        let merge_runtime = runtime.clone();
+        let dir: Arc<Path> = dir.into().into();
+        let sort_cmp = cmp.clone();
+        let sort_dir = dir.clone();
+
+        // sort chunks in parallel and write them to run files
+        let runs = self.chunks(chunk_size).par_map(params, move |mut chunk| {
+            let cmp = sort_cmp.clone();
+            let dir = sort_dir.clone();
+
+            move || {
+                chunk.sort_by(cmp);
+                RunReader::create(&dir, &chunk)
+            }
+        });
+
+        // merge the run files, in several passes if there are too many runs
+        runs.try_collect::<Vec<_>>()
+            .then(move |result| async move {
+                let runs = result?;
+                let merge = move || sort::merge_runs(&dir, runs, sort::MAX_FAN_IN, cmp);
+                rt::spawn_blocking_on(merge_runtime.as_ref(), merge).await
+            })
+            .map(move |result| match result {
+                Ok(merge) => iter_blocking_on(runtime.as_ref(), buf_size, merge).left_stream(),
+                Err(err) => stream::once(future::err(err)).right_stream(),
+            })
+            .flatten_stream()
+            .boxed()
+    }
+
     fn par_for_each<P, F, Fut>(self, params: P, f: F) -> BoxFuture<'static, ()>
     where
         F: 'static + FnMut(Self::Item) -> Fut + Send,
@@ -683,16 +2231,35 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            panic_policy,
+            runtime,
+            ..
         } = params.into();
-        let stream = self
-            .stateful_map(f, |mut f, item| {
-                let fut = f(item);
-                Some((f, fut))
-            })
-            .spawned(buf_size);
-
-        let worker_futures =
-            (0..num_workers).map(move |_| rt::spawn(stream.clone().for_each(|fut| fut)));
+        let cancel = cancel.unwrap_or_default();
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            self.take_until(cancel.cancelled())
+                .stateful_map(f, |mut f, item| {
+                    let fut = panic::catch_unwind(|| f(item));
+                    Some((f, panic::catch_unwind_future(fut)))
+                }),
+        );
+
+        let worker_futures = (0..num_workers).map(move |_| {
+            rt::spawn_on(
+                runtime.as_ref(),
+                stream
+                    .clone()
+                    .then(|fut| fut)
+                    .map(move |result| {
+                        panic::apply_policy(panic_policy, result);
+                    })
+                    .take_until(cancel.cancelled())
+                    .for_each(future::ready),
+            )
+        });
 
         future::join_all(worker_futures).map(|_| ()).boxed()
     }
@@ -706,21 +2273,34 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            panic_policy,
+            runtime,
+            ..
         } = params.into();
-        let stream = self
-            .stateful_map(f, |mut f, item| {
-                let func = f(item);
-                Some((f, func))
-            })
-            .spawned(buf_size);
+        let cancel = cancel.unwrap_or_default();
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            self.take_until(cancel.cancelled())
+                .stateful_map(f, |mut f, item| {
+                    let func = panic::catch_unwind(|| f(item));
+                    Some((f, func))
+                }),
+        );
 
         let worker_futs: Vec<_> = (0..num_workers)
             .map(move |_| {
                 let mut stream = stream.clone();
+                let cancel = cancel.clone();
 
-                rt::spawn_blocking(move || {
+                rt::spawn_blocking_on(runtime.as_ref(), move || {
                     while let Some(job) = rt::block_on(stream.next()) {
-                        job();
+                        if cancel.is_cancelled() {
+                            break;
+                        }
+
+                        panic::apply_policy(panic_policy, job.and_then(panic::catch_unwind));
                     }
                 })
             })
@@ -730,14 +2310,309 @@ where
     }
 }
 
+/// Spawns a task on the runtime, or the default one if `None`, that forwards the
+/// stream to a channel.
+pub(crate) fn spawned_on<S, B>(
+    runtime: Option<&RuntimeHandle>,
+    buf_size: B,
+    stream: S,
+) -> RecvStream<'static, S::Item>
+where
+    S: 'static + Send + Stream,
+    S::Item: 'static + Send,
+    B: Into<BufSize>,
+{
+    let (tx, rx) = utils::channel(buf_size.into().get());
+
+    rt::spawn_on(runtime, async move {
+        let _ = stream.map(Ok).forward(tx.into_sink()).await;
+    });
+
+    rx.into_stream()
+}
+
+// spawned
+
+pub use spawned::*;
+
+mod spawned {
+    use super::*;
+
+    /// Stream for the [spawned_abortable()](ParStreamExt::spawned_abortable) method.
+    ///
+    /// The worker forwarding the items is aborted once the stream and all its clones are
+    /// dropped.
+    #[derive(Derivative)]
+    #[derivative(Debug, Clone(bound = ""))]
+    #[pin_project]
+    pub struct Spawned<T: 'static> {
+        #[derivative(Debug = "ignore")]
+        #[pin]
+        pub(super) stream: RecvStream<'static, T>,
+        pub(super) _task: Arc<Mutex<rt::JoinHandle<()>>>,
+    }
+
+    impl<T: 'static> Stream for Spawned<T> {
+        type Item = T;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.project().stream.poll_next(cx)
+        }
+    }
+
+    impl<T: 'static> FusedStream for Spawned<T> {
+        fn is_terminated(&self) -> bool {
+            self.stream.is_terminated()
+        }
+    }
+}
+
+// reordered
+
+pub use reordered::*;
+
+mod reordered {
+    use super::*;
+    use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+    type Permitted<T> = (Option<OwnedSemaphorePermit>, Result<T, PanicPayload>);
+    type PermittedReorder<T> =
+        ReorderEnumeratedDense<BoxStream<'static, (usize, Permitted<T>)>, Permitted<T>>;
+
+    /// Stream for the [par_then_catch_unwind()](ParStreamExt::par_then_catch_unwind)
+    /// and [par_map_catch_unwind()](ParStreamExt::par_map_catch_unwind) methods.
+    ///
+    /// It yields the outputs in the input order. If the
+    /// [reorder_capacity](crate::ParParams::reorder_capacity) is set, each item holds a permit
+    /// from the input until it is yielded.
+    #[derive(Derivative)]
+    #[derivative(Debug)]
+    #[pin_project]
+    pub struct Reordered<T: 'static> {
+        #[derivative(Debug = "ignore")]
+        #[pin]
+        pub(super) stream: TakeUntil<PermittedReorder<T>, Cancelled>,
+    }
+
+    impl<T: 'static> Reordered<T> {
+        /// Returns the maximum number of items that were buffered for reordering so far.
+        pub fn high_water_mark(&self) -> usize {
+            self.stream.get_ref().high_water_mark()
+        }
+    }
+
+    impl<T: 'static> ResumeUnwind<Reordered<T>> {
+        /// Returns the maximum number of items that were buffered for reordering so far.
+        pub fn high_water_mark(&self) -> usize {
+            self.stream.high_water_mark()
+        }
+    }
+
+    impl<T: 'static> Stream for Reordered<T> {
+        type Item = Result<T, PanicPayload>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            // the permit is released once the item is yielded
+            let item = ready!(self.project().stream.poll_next(cx));
+            Ready(item.map(|(_permit, result)| result))
+        }
+    }
+
+    impl<T: 'static> FusedStream for Reordered<T> {
+        fn is_terminated(&self) -> bool {
+            self.stream.is_terminated()
+        }
+    }
+
+    /// Enumerates the stream, taking a permit for each item if the capacity is set.
+    pub(super) fn enumerate_with_permits<S>(
+        stream: S,
+        capacity: Option<usize>,
+    ) -> impl Stream<Item = (usize, Option<OwnedSemaphorePermit>, S::Item)>
+    where
+        S: Stream,
+    {
+        let semaphore = capacity.map(|capacity| {
+            assert!(capacity > 0, "reorder_capacity must be positive");
+            Arc::new(Semaphore::new(capacity))
+        });
+
+        stream.enumerate().then(move |(index, item)| {
+            let semaphore = semaphore.clone();
+
+            async move {
+                let permit = match semaphore {
+                    Some(semaphore) => Some(semaphore.acquire_owned().await.unwrap()),
+                    None => None,
+                };
+                (index, permit, item)
+            }
+        })
+    }
+}
+
+// flattened
+
+pub use flattened::*;
+
+mod flattened {
+    use super::*;
+    use tokio::sync::OwnedSemaphorePermit;
+
+    /// The output of a worker of [par_flat_map()](ParStreamExt::par_flat_map).
+    pub(super) enum Tagged<T> {
+        /// The item at the sub-index among the items of the input at the index.
+        Item(usize, usize, Result<T, PanicPayload>),
+        /// Marks the end of the items of the input at the index.
+        Last(usize, Option<OwnedSemaphorePermit>),
+    }
+
+    struct Pending<T> {
+        num_received: usize,
+        items: VecDeque<Result<T, PanicPayload>>,
+        is_last: bool,
+        _permit: Option<OwnedSemaphorePermit>,
+    }
+
+    impl<T> Default for Pending<T> {
+        fn default() -> Self {
+            Self {
+                num_received: 0,
+                items: VecDeque::new(),
+                is_last: false,
+                _permit: None,
+            }
+        }
+    }
+
+    /// Stream for the [par_flat_map()](ParStreamExt::par_flat_map) method.
+    ///
+    /// It yields the items of the earliest unfinished input as they arrive and buffers the
+    /// items of later inputs. The index of the earliest unfinished input is published to the
+    /// workers, which hold back the later inputs that run too far ahead. If the
+    /// [reorder_capacity](crate::ParParams::reorder_capacity) is set, each input holds a permit
+    /// until its last item is yielded.
+    #[derive(Derivative)]
+    #[derivative(Debug)]
+    #[pin_project]
+    pub struct Flattened<T: 'static> {
+        commit: usize,
+        #[derivative(Debug = "ignore")]
+        commit_tx: watch::Sender<usize>,
+        #[derivative(Debug = "ignore")]
+        pending: HashMap<usize, Pending<T>>,
+        #[derivative(Debug = "ignore")]
+        #[pin]
+        stream: TakeUntil<RecvStream<'static, Tagged<T>>, Cancelled>,
+    }
+
+    impl<T: 'static> Flattened<T> {
+        pub(super) fn new(
+            stream: TakeUntil<RecvStream<'static, Tagged<T>>, Cancelled>,
+            commit_tx: watch::Sender<usize>,
+        ) -> Self {
+            Self {
+                commit: 0,
+                commit_tx,
+                pending: HashMap::new(),
+                stream,
+            }
+        }
+    }
+
+    impl<T: 'static> Stream for Flattened<T> {
+        type Item = Result<T, PanicPayload>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let mut this = self.project();
+
+            Ready(loop {
+                if let Some(pending) = this.pending.get_mut(this.commit) {
+                    if let Some(item) = pending.items.pop_front() {
+                        break Some(item);
+                    }
+
+                    if pending.is_last {
+                        this.pending.remove(this.commit);
+                        *this.commit += 1;
+                        this.commit_tx.send_replace(*this.commit);
+                        continue;
+                    }
+                }
+
+                match ready!(this.stream.as_mut().poll_next(cx)) {
+                    Some(Tagged::Item(index, sub_index, item)) => {
+                        let pending = this.pending.entry(index).or_default();
+                        assert_eq!(
+                            sub_index, pending.num_received,
+                            "the items of the input {} are out of order",
+                            index
+                        );
+                        pending.num_received += 1;
+
+                        // the item of the earliest input is yielded without buffering
+                        if index == *this.commit {
+                            break Some(item);
+                        }
+                        pending.items.push_back(item);
+                    }
+                    Some(Tagged::Last(index, permit)) => {
+                        let pending = this.pending.entry(index).or_default();
+                        pending.is_last = true;
+                        pending._permit = permit;
+                    }
+                    None => break None,
+                }
+            })
+        }
+    }
+
+    impl<T: 'static> FusedStream for Flattened<T> {
+        fn is_terminated(&self) -> bool {
+            self.stream.is_terminated()
+        }
+    }
+}
+
 // tests
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::async_test;
+    use crate::{
+        cancel::CancellationToken,
+        config::PanicPolicy,
+        utils::{async_test, DropGuard},
+    };
     use rand::prelude::*;
-    use std::time::Duration;
+    use std::{collections::HashSet, time::Duration};
+
+    /// Delegates to the default runtime and counts the spawned tasks.
+    struct CountingRuntime(Arc<AtomicUsize>);
+
+    impl rt::Runtime for CountingRuntime {
+        fn block_on(&self, fut: BoxFuture<'_, ()>) {
+            rt::block_on(fut)
+        }
+
+        fn block_on_executor(&self, fut: BoxFuture<'_, ()>) {
+            rt::block_on(fut)
+        }
+
+        fn spawn(&self, fut: BoxFuture<'static, ()>) -> Box<dyn rt::SpawnHandle> {
+            self.0.fetch_add(1, SeqCst);
+            Box::new(rt::spawn(fut))
+        }
+
+        fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) -> Box<dyn rt::SpawnHandle> {
+            self.0.fetch_add(1, SeqCst);
+            Box::new(rt::spawn_blocking(f))
+        }
+
+        fn sleep(&self, dur: Duration) -> Box<dyn rt::SleepHandle> {
+            Box::new(rt::sleep(dur).boxed())
+        }
+    }
 
     async_test! {
         async fn par_batching_test() {
@@ -780,6 +2655,423 @@ mod tests {
         }
 
 
+        async fn par_then_panic_test() {
+            use std::panic::AssertUnwindSafe;
+
+            let f = |value: usize| async move {
+                rt::sleep(Duration::from_millis(value as u64 % 5)).await;
+                assert!(value != 2, "unexpected value");
+                value
+            };
+
+            {
+                let params = ParParams {
+                    panic_policy: PanicPolicy::Skip,
+                    ..ParParams::default()
+                };
+                let vec: Vec<_> = stream::iter(0..5).par_then(params, f).collect().await;
+                assert_eq!(vec, [0, 1, 3, 4]);
+            }
+
+            {
+                let result = AssertUnwindSafe(stream::iter(0..5).par_then(None, f).collect::<Vec<_>>())
+                    .catch_unwind()
+                    .await;
+                assert!(result.is_err());
+            }
+
+            {
+                let vec: Vec<_> = stream::iter(0..5)
+                    .par_map_catch_unwind(None, |value: usize| {
+                        move || {
+                            assert!(value != 2, "unexpected value");
+                            value
+                        }
+                    })
+                    .collect()
+                    .await;
+                assert!(matches!(
+                    *vec,
+                    [Ok(0), Ok(1), Err(ref err), Ok(3), Ok(4)] if err.message() == Some("unexpected value")
+                ));
+            }
+        }
+
+
+        async fn par_then_unordered_panic_test() {
+            use std::panic::AssertUnwindSafe;
+
+            // a single worker, which must survive the panic to process the later items
+            let params = || ParParams {
+                num_workers: 1,
+                ..ParParams::default()
+            };
+            let f = |value: usize| async move {
+                assert!(value != 2, "unexpected value");
+                value
+            };
+
+            {
+                let result = AssertUnwindSafe(
+                    stream::iter(0..5)
+                        .par_then_unordered(params(), f)
+                        .collect::<Vec<_>>(),
+                )
+                .catch_unwind()
+                .await;
+                let payload = PanicPayload::new(result.unwrap_err());
+                assert_eq!(payload.message(), Some("unexpected value"));
+            }
+
+            {
+                let mut vec: Vec<_> = stream::iter(0..5)
+                    .par_then_unordered_catch_unwind(params(), f)
+                    .collect()
+                    .await;
+                vec.sort_by_key(|result| *result.as_ref().unwrap_or(&2));
+                assert!(matches!(
+                    *vec,
+                    [Ok(0), Ok(1), Err(ref err), Ok(3), Ok(4)] if err.message() == Some("unexpected value")
+                ));
+            }
+
+            {
+                let params = ParParams {
+                    panic_policy: PanicPolicy::Skip,
+                    ..params()
+                };
+                let mut vec: Vec<_> = stream::iter(0..5)
+                    .par_map_unordered(params, |value: usize| {
+                        move || {
+                            assert!(value != 2, "unexpected value");
+                            value
+                        }
+                    })
+                    .collect()
+                    .await;
+                vec.sort_unstable();
+                assert_eq!(vec, [0, 1, 3, 4]);
+            }
+
+            {
+                let result = AssertUnwindSafe(
+                    stream::iter(0..5)
+                        .par_flat_map_unordered(params(), |value: usize| {
+                            assert!(value != 2, "unexpected value");
+                            stream::iter([value])
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .catch_unwind()
+                .await;
+                assert!(result.is_err());
+            }
+
+            {
+                let result = AssertUnwindSafe(
+                    stream::iter(0..5)
+                        .par_then_sharded(params(), |&value| value, move |_, value| f(value))
+                        .collect::<Vec<_>>(),
+                )
+                .catch_unwind()
+                .await;
+                assert!(result.is_err());
+            }
+        }
+
+
+        async fn par_then_timeout_test() {
+            let vec: Vec<_> = stream::iter([5u64, 1000, 5, 1000, 5])
+                .par_then_timeout(None, Duration::from_millis(100), |millis| async move {
+                    rt::sleep(Duration::from_millis(millis)).await;
+                    millis
+                })
+                .collect()
+                .await;
+
+            assert!(matches!(*vec, [Ok(5), Err(_), Ok(5), Err(_), Ok(5)]));
+        }
+
+        async fn par_then_sharded_test() {
+            let params = ParParams {
+                num_workers: 4,
+                ..ParParams::default()
+            };
+            let outputs: Vec<_> = stream::iter(0..1000u64)
+                .par_then_sharded(params, |value| value % 10, {
+                    let mut counts = HashMap::new();
+
+                    move |worker_index, value| {
+                        let count = counts.entry(value % 10).or_insert(0);
+                        *count += 1;
+                        let output = (value % 10, worker_index, *count);
+                        async move { output }
+                    }
+                })
+                .collect()
+                .await;
+
+            assert_eq!(outputs.len(), 1000);
+
+            for key in 0..10 {
+                let outputs: Vec<_> = outputs.iter().filter(|(k, _, _)| *k == key).collect();
+                let (_, worker_index, _) = *outputs[0];
+
+                // the key stays on the same worker and sees its items in order
+                assert!(outputs.iter().all(|&&(_, index, _)| index == worker_index));
+                itertools::assert_equal(outputs.iter().map(|&&(_, _, count)| count), 1..=100);
+            }
+
+            // zero workers are rejected up front
+            let params = ParParams {
+                num_workers: 0,
+                ..ParParams::default()
+            };
+            let result = panic::catch_unwind(|| {
+                stream::iter(0..10u64).par_then_sharded(params, |value| *value, |_, value| async move {
+                    value
+                })
+            });
+            let payload = result.err().unwrap();
+            assert_eq!(payload.message(), Some("num_workers must be positive"));
+        }
+
+        async fn par_map_init_test() {
+            let num_inits = Arc::new(AtomicUsize::new(0));
+            let params = ParParams {
+                num_workers: 4,
+                ..ParParams::default()
+            };
+
+            let outputs: Vec<_> = stream::iter(0..1000)
+                .par_map_init(
+                    params,
+                    {
+                        let num_inits = num_inits.clone();
+                        move |worker_index| {
+                            num_inits.fetch_add(1, SeqCst);
+                            (worker_index, 0)
+                        }
+                    },
+                    |(_, count): &mut (usize, usize), value| {
+                        *count += 1;
+                        (value, *count)
+                    },
+                )
+                .collect()
+                .await;
+
+            // the state is created once per worker and kept across items
+            assert!(num_inits.load(SeqCst) <= 4);
+            itertools::assert_equal(outputs.iter().map(|&(value, _)| value), 0..1000);
+            assert!(outputs.iter().any(|&(_, count)| count > 1));
+        }
+
+        async fn par_flat_map_test() {
+            let expect: Vec<_> = (0..100u64)
+                .flat_map(|value| iter::repeat(value).take((value % 4) as usize))
+                .collect();
+
+            let values: Vec<_> = stream::iter(0..100u64)
+                .par_flat_map(None, |value| {
+                    // items producing more outputs are slower
+                    stream::iter(0..value % 4).then(move |_| async move {
+                        rt::sleep(Duration::from_millis(value % 4)).await;
+                        value
+                    })
+                })
+                .collect()
+                .await;
+            assert_eq!(values, expect);
+
+            let mut values: Vec<_> = stream::iter(0..100u64)
+                .par_flat_map_unordered(None, |value| {
+                    stream::iter(iter::repeat(value).take((value % 4) as usize))
+                })
+                .collect()
+                .await;
+            values.sort_unstable();
+            assert_eq!(values, expect);
+
+            let values: Vec<_> = stream::iter(0..100u64)
+                .par_flat_map_iter(None, |value| iter::repeat(value).take((value % 4) as usize))
+                .collect()
+                .await;
+            assert_eq!(values, expect);
+
+            let mut values: Vec<_> = stream::iter(0..100u64)
+                .par_flat_map_iter_unordered(None, |value| vec![value; (value % 4) as usize])
+                .collect()
+                .await;
+            values.sort_unstable();
+            assert_eq!(values, expect);
+        }
+
+        async fn par_flat_map_streaming_test() {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let mut rx = Some(rx);
+
+            // the second item is produced only after the first one is consumed
+            let mut stream = stream::iter(0..1).par_flat_map(None, move |_| {
+                let rx = rx.take().unwrap();
+                stream::once(future::ready(0)).chain(stream::once(rx).map(|_| 1))
+            });
+            assert_eq!(stream.next().await, Some(0));
+            tx.send(()).unwrap();
+            assert_eq!(stream.next().await, Some(1));
+            assert_eq!(stream.next().await, None);
+        }
+
+        async fn par_flat_map_bounded_test() {
+            let params = ParParams {
+                num_workers: 2,
+                buf_size: Some(2),
+                ..ParParams::default()
+            };
+            let num_polled = Arc::new(AtomicUsize::new(0));
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let mut rx = Some(rx);
+
+            // the first input is held back while the second one produces many items
+            let mut stream = stream::iter(0..2).par_flat_map(params, {
+                let num_polled = num_polled.clone();
+
+                move |value| {
+                    let first = rx.take().map(|rx| stream::once(rx).map(|_| 0));
+                    let num_polled = num_polled.clone();
+                    let rest = stream::iter(1..=100).inspect(move |_| {
+                        num_polled.fetch_add(1, SeqCst);
+                    });
+
+                    if value == 0 {
+                        first.unwrap().left_stream()
+                    } else {
+                        rest.right_stream()
+                    }
+                }
+            });
+
+            // the second input stops polling its stream at buf_size items ahead
+            rt::sleep(Duration::from_millis(50)).await;
+            assert!(num_polled.load(SeqCst) <= 3);
+
+            tx.send(()).unwrap();
+            let values: Vec<_> = (&mut stream).collect().await;
+            itertools::assert_equal(values, 0..=100);
+        }
+
+        async fn par_then_keyed_test() {
+            let params = ParParams {
+                num_workers: 8,
+                ..ParParams::default()
+            };
+            let outputs: Vec<_> = stream::iter(0..200u64)
+                .par_then_keyed(params, |value| value % 2, |value| async move {
+                    // the items of key 0 are slow
+                    let millis = if value % 2 == 0 { 10 } else { 0 };
+                    rt::sleep(Duration::from_millis(millis)).await;
+                    value
+                })
+                .collect()
+                .await;
+
+            // the fast key is not held back by the slow key
+            assert_eq!(outputs[0] % 2, 1);
+
+            for key in 0..2 {
+                let values: Vec<_> = outputs.iter().copied().filter(|value| value % 2 == key).collect();
+                let expect: Vec<_> = (0..200).filter(|value| value % 2 == key).collect();
+                assert_eq!(values, expect);
+            }
+        }
+
+        async fn par_then_custom_runtime_test() {
+            let num_spawned = Arc::new(AtomicUsize::new(0));
+            let params = ParParams {
+                runtime: Some(RuntimeHandle::new(CountingRuntime(num_spawned.clone()))),
+                ..4.into()
+            };
+
+            let doubled: Vec<_> = stream::iter(0..100)
+                .par_then(params, |val| async move { val * 2 })
+                .collect()
+                .await;
+            assert!(doubled.into_iter().eq((0..100).map(|val| val * 2)));
+            assert!(num_spawned.load(SeqCst) > 0);
+        }
+
+        async fn runtime_handle_test() {
+            let num_spawned = Arc::new(AtomicUsize::new(0));
+            let runtime = RuntimeHandle::new(CountingRuntime(num_spawned.clone()));
+
+            // the outputs are sent back through the remote handles
+            let output = runtime.spawn(async move { 1 + 2 }).await;
+            assert_eq!(output, 3);
+
+            let output = runtime.spawn_blocking(|| vec!["blocking".to_string(); 2]).await;
+            assert_eq!(output, ["blocking", "blocking"]);
+
+            let output = rt::spawn_blocking_on(Some(&runtime), || 4u64.pow(2)).await;
+            assert_eq!(output, 16);
+            assert_eq!(num_spawned.load(SeqCst), 3);
+        }
+
+        async fn spawned_abort_test() {
+            // abort a task
+            let (guard, dropped_rx) = DropGuard::new();
+            let handle = rt::spawn(async move {
+                let _guard = guard;
+                future::pending::<()>().await;
+            });
+            assert!(!handle.is_finished());
+
+            handle.abort();
+            assert_eq!(dropped_rx.await, Ok(false));
+
+            // the worker of spawned_abortable() is aborted after all receivers are dropped
+            let (guard, mut dropped_rx) = DropGuard::new();
+            let stream = stream::iter(0..10)
+                .chain(stream::pending())
+                .map(move |val| {
+                    let _ = &guard;
+                    val
+                });
+            let mut recv1 = stream.spawned_abortable(None);
+            let recv2 = recv1.clone();
+
+            let values: Vec<_> = (&mut recv1).take(10).collect().await;
+            assert_eq!(values, (0..10).collect::<Vec<_>>());
+
+            drop(recv1);
+            assert!(dropped_rx.try_recv().is_err());
+
+            drop(recv2);
+            assert_eq!(dropped_rx.await, Ok(false));
+        }
+
+        async fn par_then_reorder_capacity_test() {
+            let params = ParParams {
+                num_workers: 8,
+                reorder_capacity: Some(4),
+                ..ParParams::default()
+            };
+            let mut stream = stream::iter(0..100u64).par_then(params, |value| async move {
+                // the first item is the slowest one
+                let millis = if value == 0 { 100 } else { 1 };
+                rt::sleep(Duration::from_millis(millis)).await;
+                value
+            });
+
+            let mut expect = 0;
+            while let Some(value) = stream.next().await {
+                assert_eq!(value, expect);
+                expect += 1;
+            }
+            assert_eq!(expect, 100);
+            assert!(stream.high_water_mark() <= 4);
+        }
+
+
         async fn par_then_unordered_test() {
             let max = 1000u64;
             let mut values: Vec<_> = stream::iter((0..max).into_iter())
@@ -815,6 +3107,115 @@ mod tests {
         }
 
 
+        async fn par_fold_test() {
+            {
+                let text = stream::iter(iter::empty::<String>())
+                    .par_fold(None, 4, String::new(), |lhs, rhs| async move { lhs + &rhs })
+                    .await;
+                assert_eq!(text, "");
+            }
+
+            {
+                let mut rng = rand::thread_rng();
+                let values: Vec<u64> = (0..1000).map(|_| rng.gen_range(0..10)).collect();
+                let expect: String = values.iter().map(|value| value.to_string()).collect();
+
+                let text = stream::iter(values)
+                    .map(|value| value.to_string())
+                    .par_fold(None, 7, String::new(), |lhs, rhs| async move {
+                        rt::sleep(Duration::from_micros(rhs.len() as u64 * 10)).await;
+                        lhs + &rhs
+                    })
+                    .await;
+                assert_eq!(text, expect);
+            }
+        }
+
+        async fn par_sort_by_test() {
+            let mut rng = rand::thread_rng();
+            let values: Vec<(u8, usize)> = (0..10_000).map(|index| (rng.gen(), index)).collect();
+
+            // the sort is stable
+            let mut expect = values.clone();
+            expect.sort_by_key(|&(key, _)| key);
+
+            let sorted: Vec<_> = stream::iter(values.clone())
+                .par_sort_by_key(None, 100, |&(key, _)| key)
+                .collect()
+                .await;
+            assert_eq!(sorted, expect);
+
+            let dir = std::env::temp_dir().join(format!("par-stream-sort-test-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+
+            // the 100 runs are more than the fan-in and are merged in two passes
+            let sorted: Vec<_> = stream::iter(values)
+                .par_sort_by_spilled(None, 100, &dir, |lhs, rhs| lhs.0.cmp(&rhs.0))
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(sorted, expect);
+
+            // the run files are removed
+            assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+            std::fs::remove_dir(&dir).unwrap();
+        }
+
+        async fn par_sort_by_custom_runtime_test() {
+            let num_spawned = Arc::new(AtomicUsize::new(0));
+            let params = ParParams {
+                runtime: Some(RuntimeHandle::new(CountingRuntime(num_spawned.clone()))),
+                ..4.into()
+            };
+            let expect: Vec<_> = (0..100u64).collect();
+
+            // the tasks spawned to sort the chunks
+            let _: Vec<_> = stream::iter(0..100u64)
+                .chunks(10)
+                .par_map(params.clone(), |chunk| move || chunk)
+                .collect()
+                .await;
+            let num_sort_tasks = num_spawned.swap(0, SeqCst);
+
+            // the merge runs on the same runtime
+            let sorted: Vec<_> = stream::iter((0..100u64).rev())
+                .par_sort_by(params.clone(), 10, |lhs, rhs| lhs.cmp(rhs))
+                .collect()
+                .await;
+            assert_eq!(sorted, expect);
+            assert_eq!(num_spawned.swap(0, SeqCst), num_sort_tasks + 1);
+
+            let dir = std::env::temp_dir().join(format!("par-stream-sort-runtime-test-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+
+            let sorted: Vec<_> = stream::iter((0..100u64).rev())
+                .par_sort_by_spilled(params, 10, &dir, |lhs, rhs| lhs.cmp(rhs))
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(sorted, expect);
+            assert_eq!(num_spawned.load(SeqCst), num_sort_tasks + 2);
+            std::fs::remove_dir(&dir).unwrap();
+        }
+
+        async fn par_fold_by_key_test() {
+            let max = 10_000u64;
+            let sums = stream::iter(0..max)
+                .par_fold_by_key(
+                    None,
+                    |value| value % 7,
+                    || 0,
+                    |sum, value| async move { sum + value },
+                    |lhs, rhs| async move { lhs + rhs },
+                )
+                .await;
+
+            assert_eq!(sums.len(), 7);
+            for (key, sum) in sums {
+                assert_eq!(sum, (0..max).filter(|value| value % 7 == key).sum::<u64>());
+            }
+        }
+
         async fn reorder_index_haling_test() {
             let indexes = vec![5, 2, 1, 0, 6, 4, 3];
             let output: Vec<_> = stream::iter(indexes)
@@ -888,6 +3289,75 @@ mod tests {
         }
 
 
+        async fn for_each_cancel_test() {
+            let token = CancellationToken::new();
+            let params = ParParams {
+                cancel: Some(token.clone()),
+                ..ParParams::default()
+            };
+            let (input_tx, input_rx) = flume::unbounded();
+            let (processed_tx, processed_rx) = flume::unbounded();
+
+            let future = input_rx
+                .into_stream()
+                .par_for_each(params, move |value: usize| {
+                    let processed_tx = processed_tx.clone();
+                    async move {
+                        let _ = processed_tx.send_async(value).await;
+                    }
+                });
+            let handle = rt::spawn(future);
+
+            for value in 0..10 {
+                input_tx.send(value).unwrap();
+            }
+            let processed: HashSet<_> = processed_rx.stream().take(10).collect().await;
+            token.cancel();
+
+            // the input is still open, but items sent after cancellation are not processed
+            for value in 10..20 {
+                let _ = input_tx.send(value);
+            }
+            handle.await;
+
+            assert_eq!(processed, (0..10).collect());
+            assert!(processed_rx.try_recv().is_err());
+        }
+
+
+        async fn par_then_cancel_test() {
+            let token = CancellationToken::new();
+            let params = ParParams {
+                cancel: Some(token.clone()),
+                ..ParParams::default()
+            };
+            let (input_tx, input_rx) = flume::unbounded();
+            let processed = Arc::new(AtomicUsize::new(0));
+
+            let mut stream = input_rx.into_stream().par_then(params, {
+                let processed = processed.clone();
+                move |value: usize| {
+                    processed.fetch_add(1, SeqCst);
+                    async move { value }
+                }
+            });
+
+            for value in 0..10 {
+                input_tx.send(value).unwrap();
+            }
+            for expect in 0..10 {
+                assert_eq!(stream.next().await, Some(expect));
+            }
+            token.cancel();
+
+            for value in 10..20 {
+                let _ = input_tx.send(value);
+            }
+            assert!(stream.next().await.is_none());
+            assert_eq!(processed.load(SeqCst), 10);
+        }
+
+
         async fn tee_halt_test() {
             let mut rx1 = stream::iter(0..).tee(1);
             let mut rx2 = rx1.clone();
@@ -0,0 +1,125 @@
+use crate::common::*;
+use std::{collections::hash_map::RandomState, hash::BuildHasher};
+
+/// The retry policy for [try_par_then_retry()](crate::TryParStreamExt::try_par_then_retry).
+///
+/// It configures the maximum number of attempts, the [Backoff] between attempts, optional
+/// jitter and a predicate deciding which errors are worth retrying.
+///
+/// ```rust
+/// use par_stream::{Backoff, RetryPolicy};
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::<std::io::Error>::new(5)
+///     .backoff(Backoff::Exponential {
+///         initial: Duration::from_millis(10),
+///         factor: 2.0,
+///         max: Duration::from_secs(1),
+///     })
+///     .jitter(true)
+///     .retry_if(|err| err.kind() == std::io::ErrorKind::TimedOut);
+/// ```
+#[derive(Derivative)]
+#[derivative(Debug, Clone(bound = ""))]
+pub struct RetryPolicy<E> {
+    /// The maximum number of attempts including the first one.
+    pub max_attempts: usize,
+    /// The waiting time between attempts.
+    pub backoff: Backoff,
+    /// Randomly scales each delay into `[delay / 2, delay)` if enabled.
+    pub jitter: bool,
+    #[derivative(Debug = "ignore")]
+    retry_if: Arc<dyn Fn(&E) -> bool + Send + Sync>,
+}
+
+impl<E> RetryPolicy<E> {
+    /// Creates a policy that retries every error up to `max_attempts` attempts without delay.
+    ///
+    /// # Panics
+    /// The `max_attempts` must be positive.
+    pub fn new(max_attempts: usize) -> Self {
+        assert!(max_attempts > 0, "max_attempts must be positive");
+
+        Self {
+            max_attempts,
+            backoff: Backoff::Fixed(Duration::ZERO),
+            jitter: false,
+            retry_if: Arc::new(|_| true),
+        }
+    }
+
+    /// Sets the backoff strategy.
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Enables or disables the jitter on delays.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the predicate on errors. Errors not satisfying the predicate are returned immediately.
+    pub fn retry_if<F>(mut self, predicate: F) -> Self
+    where
+        F: 'static + Fn(&E) -> bool + Send + Sync,
+    {
+        self.retry_if = Arc::new(predicate);
+        self
+    }
+
+    /// Returns `true` if another attempt should be made after `attempt` attempts fail with `err`.
+    pub fn should_retry(&self, attempt: usize, err: &E) -> bool {
+        attempt < self.max_attempts && (self.retry_if)(err)
+    }
+
+    /// Returns the delay before the next attempt after `attempt` attempts fail.
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let delay = self.backoff.delay(attempt);
+
+        if self.jitter {
+            let random = RandomState::new().hash_one(attempt);
+            let scale = 0.5 + (random as f64 / u64::MAX as f64) * 0.5;
+            delay.mul_f64(scale)
+        } else {
+            delay
+        }
+    }
+}
+
+/// The waiting time strategy between attempts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backoff {
+    /// Waits for a constant duration.
+    Fixed(Duration),
+    /// Waits for `initial * factor^(attempt - 1)`, bounded by `max`.
+    Exponential {
+        initial: Duration,
+        factor: f64,
+        max: Duration,
+    },
+}
+
+impl Backoff {
+    /// Returns the delay after `attempt` attempts fail.
+    pub fn delay(&self, attempt: usize) -> Duration {
+        match *self {
+            Self::Fixed(delay) => delay,
+            Self::Exponential {
+                initial,
+                factor,
+                max,
+            } => {
+                let exp = attempt.saturating_sub(1).min(i32::MAX as usize) as i32;
+                let secs = initial.as_secs_f64() * factor.powi(exp);
+
+                if secs.is_finite() && secs < max.as_secs_f64() {
+                    Duration::from_secs_f64(secs)
+                } else {
+                    max
+                }
+            }
+        }
+    }
+}
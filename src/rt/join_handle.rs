@@ -0,0 +1,108 @@
+use super::{NativeJoinHandle, RuntimeJoinHandle, SpawnHandle};
+use crate::common::*;
+
+/// A handle to a spawned task. Awaiting on the handle returns the output of the task.
+///
+/// The task is detached when the handle is dropped, unless
+/// [abort_on_drop()](JoinHandle::abort_on_drop) is set.
+pub struct JoinHandle<T> {
+    inner: Inner<T>,
+    abort_on_drop: bool,
+}
+
+enum Inner<T> {
+    Native(NativeJoinHandle<T>),
+    Runtime(RuntimeJoinHandle<T>),
+    /// The task is aborted, and the output is yielded once if present.
+    Aborted(Option<T>),
+}
+
+impl<T> JoinHandle<T> {
+    pub(crate) fn native(handle: NativeJoinHandle<T>) -> Self {
+        Self {
+            inner: Inner::Native(handle),
+            abort_on_drop: false,
+        }
+    }
+
+    pub(crate) fn runtime(handle: RuntimeJoinHandle<T>) -> Self {
+        Self {
+            inner: Inner::Runtime(handle),
+            abort_on_drop: false,
+        }
+    }
+
+    /// Aborts the task. The handle is consumed, so that the output cannot be awaited.
+    pub fn abort(mut self) {
+        self.abort_task();
+    }
+
+    /// Returns `true` if the task has finished or is aborted.
+    pub fn is_finished(&self) -> bool {
+        match &self.inner {
+            Inner::Native(handle) => handle.is_finished(),
+            Inner::Runtime(handle) => handle.is_finished(),
+            Inner::Aborted(_) => true,
+        }
+    }
+
+    /// Aborts the task when the handle is dropped instead of detaching it.
+    pub fn abort_on_drop(mut self) -> Self {
+        self.abort_on_drop = true;
+        self
+    }
+
+    fn abort_task(&mut self) {
+        match mem::replace(&mut self.inner, Inner::Aborted(None)) {
+            Inner::Native(handle) => handle.abort(),
+            Inner::Runtime(handle) => handle.abort(),
+            Inner::Aborted(_) => {}
+        }
+    }
+}
+
+// the output is never pinned
+impl<T> Unpin for JoinHandle<T> {}
+
+impl<T> Future for JoinHandle<T>
+where
+    T: 'static,
+{
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match &mut self.inner {
+            Inner::Native(handle) => Pin::new(handle).poll(cx),
+            Inner::Runtime(handle) => Pin::new(handle).poll(cx),
+            Inner::Aborted(output) => Ready(output.take().expect("the output is already taken")),
+        }
+    }
+}
+
+impl<T> Drop for JoinHandle<T> {
+    fn drop(&mut self) {
+        if self.abort_on_drop {
+            self.abort_task();
+        }
+    }
+}
+
+impl<T> Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinHandle")
+            .field("abort_on_drop", &self.abort_on_drop)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SpawnHandle for JoinHandle<()> {
+    /// Aborts the task. Awaiting on the handle afterwards returns immediately.
+    fn abort(&mut self) {
+        self.abort_task();
+        self.inner = Inner::Aborted(Some(()));
+    }
+
+    fn is_finished(&self) -> bool {
+        JoinHandle::is_finished(self)
+    }
+}
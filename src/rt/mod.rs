@@ -1,10 +1,16 @@
 //! Asynchronous runtime methods.
 
-use crate::utils::{has_async_std, has_tokio, no_rt};
+use crate::utils::{has_async_std, has_smol, has_tokio, no_rt};
 
 mod runtime;
 pub use runtime::*;
 
+mod timeout;
+pub use timeout::*;
+
+mod join_handle;
+pub use join_handle::*;
+
 no_rt! {
     mod rt_custom;
     pub use rt_custom::*;
@@ -19,3 +25,8 @@ has_async_std! {
     mod rt_async_std;
     pub use rt_async_std::*;
 }
+
+has_smol! {
+    mod rt_smol;
+    pub use rt_smol::*;
+}
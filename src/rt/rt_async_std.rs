@@ -1,3 +1,4 @@
+use super::JoinHandle;
 use crate::common::*;
 
 pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
@@ -5,7 +6,14 @@ where
     F: 'static + Future + Send,
     F::Output: 'static + Send,
 {
-    JoinHandle(async_std::task::spawn(future))
+    let finished = Arc::new(AtomicBool::new(false));
+    let guard = FinishGuard(finished.clone());
+    let handle = async_std::task::spawn(async move {
+        let _guard = guard;
+        future.await
+    });
+
+    JoinHandle::native(NativeJoinHandle { handle, finished })
 }
 
 pub fn spawn_blocking<F, R>(f: F) -> JoinHandle<R>
@@ -13,7 +21,14 @@ where
     F: 'static + Send + FnOnce() -> R,
     R: 'static + Send,
 {
-    JoinHandle(async_std::task::spawn_blocking(f))
+    let finished = Arc::new(AtomicBool::new(false));
+    let guard = FinishGuard(finished.clone());
+    let handle = async_std::task::spawn_blocking(move || {
+        let _guard = guard;
+        f()
+    });
+
+    JoinHandle::native(NativeJoinHandle { handle, finished })
 }
 
 pub async fn sleep(duration: Duration) {
@@ -34,12 +49,36 @@ where
     async_std::task::block_on(future)
 }
 
-pub struct JoinHandle<T>(async_std::task::JoinHandle<T>);
+pub(crate) struct NativeJoinHandle<T> {
+    handle: async_std::task::JoinHandle<T>,
+    finished: Arc<AtomicBool>,
+}
+
+impl<T> NativeJoinHandle<T> {
+    pub(crate) fn abort(self) {
+        // the task is cancelled on the first poll
+        let _ = self.handle.cancel().now_or_never();
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.finished.load(Acquire)
+    }
+}
 
-impl<T> Future for JoinHandle<T> {
+impl<T> Future for NativeJoinHandle<T> {
     type Output = T;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        Pin::new(&mut self.0).poll(cx)
+        Pin::new(&mut self.handle).poll(cx)
+    }
+}
+
+/// Marks the task finished when dropped, which happens when the task completes, panics or
+/// is cancelled.
+struct FinishGuard(Arc<AtomicBool>);
+
+impl Drop for FinishGuard {
+    fn drop(&mut self) {
+        self.0.store(true, Release);
     }
 }
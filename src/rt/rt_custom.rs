@@ -1,32 +1,12 @@
 use crate::common::*;
-use super::{get_global_runtime, BoxAny};
+use super::{get_global_runtime, spawn_blocking_with, spawn_with, JoinHandle, RuntimeJoinHandle};
 
 pub fn spawn<Fut>(fut: Fut) -> JoinHandle<Fut::Output>
 where
     Fut: 'static + Future + Send,
     Fut::Output: 'static + Send,
 {
-    let future = async move {
-        let output = get_global_runtime()
-            .spawn(
-                async move {
-                    let output: BoxAny<'static> = Box::new(fut.await);
-                    output
-                }
-                .boxed(),
-            )
-            .await;
-
-        let output =
-            BoxAny::<'static>::downcast::<Fut::Output>(output).expect("interal error: unable downcast Box");
-        *output
-    }
-    .boxed();
-
-    JoinHandle {
-        future,
-        _phantom: PhantomData,
-    }
+    JoinHandle::native(spawn_with(get_global_runtime(), fut))
 }
 
 pub fn spawn_blocking<F, R>(f: F) -> JoinHandle<R>
@@ -34,26 +14,7 @@ where
     F: 'static + Send + FnOnce() -> R,
     R: 'static + Send,
 {
-    let future = async move {
-        let output = get_global_runtime()
-            .spawn_blocking({
-                let f: Box<dyn FnOnce() -> BoxAny<'static> + Send> = Box::new(move || {
-                    let output: BoxAny<'static> = Box::new(f());
-                    output
-                });
-                f
-            })
-            .await;
-
-        let output = BoxAny::<'static>::downcast::<R>(output).expect("interal error: unable downcast Box");
-        *output
-    }
-    .boxed();
-
-    JoinHandle {
-        future,
-        _phantom: PhantomData,
-    }
+    JoinHandle::native(spawn_blocking_with(get_global_runtime(), f))
 }
 
 pub async fn sleep(dur: Duration) {
@@ -65,16 +26,14 @@ where
     F: Future + Send,
     F::Output: 'static + Send,
 {
-    let output = get_global_runtime().block_on(
-        async move {
-            let output: BoxAny<'static> = Box::new(future.await);
-            output
+    let mut output = None;
+    get_global_runtime().block_on(
+        async {
+            output = Some(future.await);
         }
         .boxed(),
     );
-
-    let output = BoxAny::<'static>::downcast::<F::Output>(output).expect("interal error: unable downcast Box");
-    *output
+    output.expect("the runtime returned before the future completes")
 }
 
 pub fn block_on_executor<F>(future: F) -> F::Output
@@ -82,30 +41,14 @@ where
     F: 'static + Future + Send,
     F::Output: 'static + Send,
 {
-    let output = get_global_runtime().block_on(
+    let mut output = None;
+    get_global_runtime().block_on_executor(
         async {
-            let output: BoxAny<'static> = Box::new(future.await);
-            output
+            output = Some(future.await);
         }
         .boxed(),
     );
-
-    let output = BoxAny::<'static>::downcast::<F::Output>(output).expect("interal error: unable downcast Box");
-    *output
-}
-
-#[repr(transparent)]
-#[pin_project]
-pub struct JoinHandle<T> {
-    _phantom: PhantomData<T>,
-    #[pin]
-    future: BoxFuture<'static, T>,
+    output.expect("the runtime returned before the future completes")
 }
 
-impl<T> Future for JoinHandle<T> {
-    type Output = T;
-
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        self.project().future.poll_unpin(cx)
-    }
-}
+pub(crate) type NativeJoinHandle<T> = RuntimeJoinHandle<T>;
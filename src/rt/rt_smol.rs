@@ -0,0 +1,84 @@
+use super::JoinHandle;
+use crate::common::*;
+use smol::{Executor, Task};
+use std::thread;
+
+/// The executor shared by spawned tasks, run by one thread per CPU.
+static EXECUTOR: Lazy<Executor<'static>> = Lazy::new(|| {
+    for index in 0..num_cpus::get() {
+        thread::Builder::new()
+            .name(format!("par-stream-smol-{}", index))
+            .spawn(|| smol::block_on(EXECUTOR.run(future::pending::<()>())))
+            .expect("unable to spawn an executor thread");
+    }
+
+    Executor::new()
+});
+
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: 'static + Future + Send,
+    F::Output: 'static + Send,
+{
+    JoinHandle::native(NativeJoinHandle(Some(EXECUTOR.spawn(future))))
+}
+
+pub fn spawn_blocking<F, R>(f: F) -> JoinHandle<R>
+where
+    F: 'static + Send + FnOnce() -> R,
+    R: 'static + Send,
+{
+    JoinHandle::native(NativeJoinHandle(Some(smol::unblock(f))))
+}
+
+pub async fn sleep(duration: Duration) {
+    smol::Timer::after(duration).await;
+}
+
+pub fn block_on<F>(future: F) -> F::Output
+where
+    F: Future,
+{
+    smol::block_on(future)
+}
+
+pub fn block_on_executor<F>(future: F) -> F::Output
+where
+    F: Future,
+{
+    smol::block_on(EXECUTOR.run(future))
+}
+
+/// The task is detached on drop, so that it keeps running like those of other runtimes.
+pub(crate) struct NativeJoinHandle<T>(Option<Task<T>>);
+
+impl<T> NativeJoinHandle<T> {
+    /// Dropping the task cancels it.
+    pub(crate) fn abort(mut self) {
+        self.0 = None;
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        match &self.0 {
+            Some(task) => task.is_finished(),
+            None => true,
+        }
+    }
+}
+
+impl<T> Future for NativeJoinHandle<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let task = self.0.as_mut().expect("the task is already detached");
+        Pin::new(task).poll(cx)
+    }
+}
+
+impl<T> Drop for NativeJoinHandle<T> {
+    fn drop(&mut self) {
+        if let Some(task) = self.0.take() {
+            task.detach();
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use super::{JoinHandle, RuntimeHandle, SleepHandle, SpawnHandle};
 use crate::common::*;
 use tokio::runtime::{Handle, Runtime};
 
@@ -6,7 +7,7 @@ where
     F: 'static + Future + Send,
     F::Output: 'static + Send,
 {
-    JoinHandle(tokio::spawn(future))
+    JoinHandle::native(NativeJoinHandle(tokio::spawn(future)))
 }
 
 pub fn spawn_blocking<F, R>(f: F) -> JoinHandle<R>
@@ -14,7 +15,7 @@ where
     F: 'static + Send + FnOnce() -> R,
     R: 'static + Send,
 {
-    JoinHandle(tokio::task::spawn_blocking(f))
+    JoinHandle::native(NativeJoinHandle(tokio::task::spawn_blocking(f)))
 }
 
 pub async fn sleep(duration: Duration) {
@@ -35,12 +36,74 @@ where
     Runtime::new().unwrap().block_on(future)
 }
 
-pub struct JoinHandle<T>(tokio::task::JoinHandle<T>);
+pub(crate) struct NativeJoinHandle<T>(tokio::task::JoinHandle<T>);
 
-impl<T> Future for JoinHandle<T> {
+impl<T> NativeJoinHandle<T> {
+    pub(crate) fn abort(self) {
+        self.0.abort();
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.0.is_finished()
+    }
+}
+
+impl<T> Future for NativeJoinHandle<T> {
     type Output = T;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        Pin::new(&mut self.0).poll(cx).map(|result| result.unwrap())
+        Pin::new(&mut self.0).poll(cx).map(|result| match result {
+            Ok(output) => output,
+            Err(err) => match err.try_into_panic() {
+                Ok(payload) => std::panic::resume_unwind(payload),
+                Err(err) => panic!("{}", err),
+            },
+        })
+    }
+}
+
+impl From<Handle> for RuntimeHandle {
+    fn from(handle: Handle) -> Self {
+        RuntimeHandle::new(TokioRuntime(handle))
     }
 }
+
+/// Spawns tasks on the runtime of the tokio handle.
+struct TokioRuntime(Handle);
+
+impl super::Runtime for TokioRuntime {
+    fn block_on(&self, fut: BoxFuture<'_, ()>) {
+        self.0.block_on(fut)
+    }
+
+    fn block_on_executor(&self, fut: BoxFuture<'_, ()>) {
+        self.0.block_on(fut)
+    }
+
+    fn spawn(&self, fut: BoxFuture<'static, ()>) -> Box<dyn SpawnHandle> {
+        Box::new(JoinHandle::native(NativeJoinHandle(self.0.spawn(fut))))
+    }
+
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) -> Box<dyn SpawnHandle> {
+        Box::new(JoinHandle::native(NativeJoinHandle(
+            self.0.spawn_blocking(f),
+        )))
+    }
+
+    fn sleep(&self, dur: Duration) -> Box<dyn SleepHandle> {
+        let _guard = self.0.enter();
+        Box::new(TokioSleep(Box::pin(tokio::time::sleep(dur))))
+    }
+}
+
+struct TokioSleep(Pin<Box<tokio::time::Sleep>>);
+
+impl Future for TokioSleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.as_mut().poll(cx)
+    }
+}
+
+impl SleepHandle for TokioSleep {}
@@ -1,45 +1,62 @@
+use super::JoinHandle;
 use crate::common::*;
-use std::any::Any;
+use futures::future::RemoteHandle;
 
 static GLOBAL_RUNTIME: OnceCell<Box<dyn Runtime>> = OnceCell::new();
 
-pub(crate) type BoxAny<'a> = Box<dyn 'a + Send + Any>;
-
-pub unsafe trait Runtime
+/// A custom runtime on which futures and blocking functions run.
+///
+/// The futures and functions passed to the runtime return `()`. Their outputs and
+/// panics are sent back to the caller through channels, so that the runtime needs not
+/// to know the output types.
+pub trait Runtime
 where
     Self: 'static + Sync + Send,
 {
-    fn block_on<'a>(&self, fut: BoxFuture<'a, BoxAny<'static>>) -> BoxAny<'static>;
+    /// Runs the future to completion on the current thread.
+    fn block_on(&self, fut: BoxFuture<'_, ()>);
 
-    fn block_on_executor<'a>(&self, fut: BoxFuture<'a, BoxAny<'static>>) -> BoxAny<'static>;
+    /// Runs the future to completion while driving the executor of spawned tasks.
+    fn block_on_executor(&self, fut: BoxFuture<'_, ()>);
 
-    fn spawn(&self, fut: BoxFuture<'static, BoxAny<'static>>) -> Box<dyn SpawnHandle>;
+    /// Spawns the future. The task keeps running after the returned handle is dropped,
+    /// and stops when [SpawnHandle::abort()] is called.
+    fn spawn(&self, fut: BoxFuture<'static, ()>) -> Box<dyn SpawnHandle>;
 
-    fn spawn_blocking(
-        &self,
-        f: Box<dyn FnOnce() -> BoxAny<'static> + Send>,
-    ) -> Box<dyn SpawnHandle>;
+    /// Runs the blocking function on a thread where blocking is acceptable.
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) -> Box<dyn SpawnHandle>;
 
+    /// Returns a future that finishes after the duration.
     fn sleep(&self, dur: Duration) -> Box<dyn SleepHandle>;
 }
 
-pub unsafe trait SpawnHandle
+/// The handle to a task spawned by a [Runtime], which finishes when the task finishes.
+pub trait SpawnHandle
 where
-    Self: Send + Future<Output = BoxAny<'static>> + Unpin,
+    Self: Send + Future<Output = ()> + Unpin,
 {
+    /// Aborts the task.
+    fn abort(&mut self);
+
+    /// Returns `true` if the task has finished.
+    fn is_finished(&self) -> bool;
 }
 
-pub unsafe trait SleepHandle
+/// The future returned by [Runtime::sleep()].
+pub trait SleepHandle
 where
     Self: Send + Future<Output = ()> + Unpin,
 {
 }
 
+impl SleepHandle for BoxFuture<'static, ()> {}
+
 #[allow(dead_code)]
-pub(crate) fn get_global_runtime() -> &'static Box<dyn Runtime> {
+pub(crate) fn get_global_runtime() -> &'static dyn Runtime {
     GLOBAL_RUNTIME
         .get()
         .expect("global runtime is not set, did you call set_global_runtime()?")
+        .as_ref()
 }
 
 /// Sets the global runtime from a [Runtime] object.
@@ -53,3 +70,163 @@ where
         .set(Box::new(runtime))
         .map_err(|_| "set_global_runtime() cannot be called more than once")
 }
+
+/// A handle to a [Runtime] on which a pipeline spawns its workers.
+///
+/// It is set to the `runtime` field of [ParParams](crate::ParParams), so that the
+/// combinators and builders receiving the parameters spawn their workers on this
+/// runtime instead of the default one. With `runtime-tokio` feature, it can be
+/// converted from a tokio [Handle](tokio::runtime::Handle).
+///
+/// ```rust
+/// # #[cfg(feature = "runtime-tokio")]
+/// # {
+/// use futures::prelude::*;
+/// use par_stream::{prelude::*, ParParams};
+///
+/// let io_runtime = tokio::runtime::Runtime::new().unwrap();
+/// let cpu_runtime = tokio::runtime::Runtime::new().unwrap();
+///
+/// let params = ParParams {
+///     runtime: Some(cpu_runtime.handle().clone().into()),
+///     ..4.into()
+/// };
+///
+/// let doubled: Vec<_> = io_runtime.block_on(async move {
+///     stream::iter(0..100)
+///         .par_map(params, |val| move || val * 2)
+///         .collect()
+///         .await
+/// });
+/// itertools::assert_equal(doubled, (0..100).map(|val| val * 2));
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct RuntimeHandle(ByAddress<Arc<dyn Runtime>>);
+
+impl RuntimeHandle {
+    /// Creates a handle from a [Runtime] object.
+    pub fn new<R>(runtime: R) -> Self
+    where
+        R: Runtime,
+    {
+        Self(ByAddress(Arc::new(runtime)))
+    }
+
+    /// Spawns a future on the runtime.
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: 'static + Future + Send,
+        F::Output: 'static + Send,
+    {
+        JoinHandle::runtime(spawn_with(&**self.0, future))
+    }
+
+    /// Runs a blocking function on the runtime.
+    pub fn spawn_blocking<F, R>(&self, f: F) -> JoinHandle<R>
+    where
+        F: 'static + Send + FnOnce() -> R,
+        R: 'static + Send,
+    {
+        JoinHandle::runtime(spawn_blocking_with(&**self.0, f))
+    }
+}
+
+impl Debug for RuntimeHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RuntimeHandle({:p})", Arc::as_ptr(&self.0))
+    }
+}
+
+/// Spawns a future on the runtime if given, or on the default runtime otherwise.
+pub(crate) fn spawn_on<F>(runtime: Option<&RuntimeHandle>, future: F) -> JoinHandle<F::Output>
+where
+    F: 'static + Future + Send,
+    F::Output: 'static + Send,
+{
+    match runtime {
+        Some(runtime) => runtime.spawn(future),
+        None => super::spawn(future),
+    }
+}
+
+/// Runs a blocking function on the runtime if given, or on the default runtime otherwise.
+pub(crate) fn spawn_blocking_on<F, R>(runtime: Option<&RuntimeHandle>, f: F) -> JoinHandle<R>
+where
+    F: 'static + Send + FnOnce() -> R,
+    R: 'static + Send,
+{
+    match runtime {
+        Some(runtime) => runtime.spawn_blocking(f),
+        None => super::spawn_blocking(f),
+    }
+}
+
+/// The handle to a task spawned on a [Runtime] object.
+///
+/// The task is detached on drop, so that it keeps running like those of other runtimes.
+pub(crate) struct RuntimeJoinHandle<T> {
+    handle: Box<dyn SpawnHandle>,
+    output: Option<RemoteHandle<T>>,
+}
+
+impl<T> RuntimeJoinHandle<T> {
+    pub(crate) fn abort(mut self) {
+        self.handle.abort();
+
+        // dropping the remote handle stops the task even if the runtime cannot abort it
+        self.output = None;
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
+
+impl<T> Future for RuntimeJoinHandle<T>
+where
+    T: 'static,
+{
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let output = self.output.as_mut().expect("the task is already detached");
+        Pin::new(output).poll(cx)
+    }
+}
+
+impl<T> Drop for RuntimeJoinHandle<T> {
+    fn drop(&mut self) {
+        if let Some(output) = self.output.take() {
+            output.forget();
+        }
+    }
+}
+
+pub(crate) fn spawn_with<F>(runtime: &dyn Runtime, future: F) -> RuntimeJoinHandle<F::Output>
+where
+    F: 'static + Future + Send,
+    F::Output: 'static + Send,
+{
+    let (future, output) = future.remote_handle();
+    RuntimeJoinHandle {
+        handle: runtime.spawn(future.boxed()),
+        output: Some(output),
+    }
+}
+
+pub(crate) fn spawn_blocking_with<F, R>(runtime: &dyn Runtime, f: F) -> RuntimeJoinHandle<R>
+where
+    F: 'static + Send + FnOnce() -> R,
+    R: 'static + Send,
+{
+    // the lazy future completes on the first poll
+    let (future, output) = future::lazy(move |_| f()).remote_handle();
+    let handle = runtime.spawn_blocking(Box::new(move || {
+        let _ = future.now_or_never();
+    }));
+    RuntimeJoinHandle {
+        handle,
+        output: Some(output),
+    }
+}
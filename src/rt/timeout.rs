@@ -0,0 +1,53 @@
+use super::sleep;
+use crate::common::*;
+
+/// Requires a future to complete within the specified duration.
+///
+/// If the future completes in time, its output is returned in `Ok`. Otherwise the future
+/// is dropped and an [Elapsed] error is returned.
+///
+/// ```rust
+/// # par_stream::rt::block_on_executor(async move {
+/// use par_stream::rt;
+/// use std::time::Duration;
+///
+/// let result = rt::timeout(Duration::from_millis(10), async { 1 }).await;
+/// assert_eq!(result, Ok(1));
+///
+/// let result = rt::timeout(Duration::from_millis(10), futures::future::pending::<()>()).await;
+/// assert!(result.is_err());
+/// # })
+/// ```
+pub async fn timeout<Fut>(duration: Duration, fut: Fut) -> Result<Fut::Output, Elapsed>
+where
+    Fut: Future,
+{
+    let fut = Box::pin(fut);
+    let sleep = Box::pin(sleep(duration));
+
+    match future::select(fut, sleep).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(((), _)) => Err(Elapsed { duration }),
+    }
+}
+
+/// Error returned by [timeout()] when the future does not complete in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Elapsed {
+    duration: Duration,
+}
+
+impl Elapsed {
+    /// Returns the duration of the timeout.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the future did not complete within {:?}", self.duration)
+    }
+}
+
+impl std::error::Error for Elapsed {}
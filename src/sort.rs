@@ -0,0 +1,456 @@
+use crate::common::*;
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+/// Types that can be written to and read back from the run files of
+/// [par_sort_by_spilled()](crate::ParStreamExt::par_sort_by_spilled).
+///
+/// ```rust
+/// use par_stream::Spill;
+/// use std::io::{self, Read, Write};
+///
+/// struct Record {
+///     id: u64,
+///     name: String,
+/// }
+///
+/// impl Spill for Record {
+///     fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+///         self.id.spill(writer)?;
+///         self.name.spill(writer)
+///     }
+///
+///     fn load<R: Read>(reader: &mut R) -> io::Result<Self> {
+///         Ok(Self {
+///             id: u64::load(reader)?,
+///             name: String::load(reader)?,
+///         })
+///     }
+/// }
+/// ```
+pub trait Spill: Sized {
+    /// Writes the value to the writer.
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    /// Reads a value written by [spill()](Spill::spill) from the reader.
+    fn load<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+macro_rules! impl_spill_for_num {
+    ($($ty:ty),*) => {
+        $(
+            impl Spill for $ty {
+                fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+
+                fn load<R: Read>(reader: &mut R) -> io::Result<Self> {
+                    let mut bytes = [0; mem::size_of::<$ty>()];
+                    reader.read_exact(&mut bytes)?;
+                    Ok(Self::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_spill_for_num!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl Spill for Vec<u8> {
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (self.len() as u64).spill(writer)?;
+        writer.write_all(self)
+    }
+
+    fn load<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let len = u64::load(reader)?;
+        let mut bytes = vec![];
+        reader.take(len).read_to_end(&mut bytes)?;
+
+        if bytes.len() as u64 != len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(bytes)
+    }
+}
+
+impl Spill for String {
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (self.len() as u64).spill(writer)?;
+        writer.write_all(self.as_bytes())
+    }
+
+    fn load<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let bytes = Vec::<u8>::load(reader)?;
+        String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl<A, B> Spill for (A, B)
+where
+    A: Spill,
+    B: Spill,
+{
+    fn spill<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.0.spill(writer)?;
+        self.1.spill(writer)
+    }
+
+    fn load<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok((A::load(reader)?, B::load(reader)?))
+    }
+}
+
+// run_reader
+
+pub(crate) use run_reader::*;
+
+mod run_reader {
+    use super::*;
+
+    static NEXT_RUN_ID: AtomicUsize = AtomicUsize::new(0);
+
+    /// Reads back the sorted items spilled to a run file, and removes the file on drop.
+    ///
+    /// The file is opened on the first read, so that only the runs being merged hold a file
+    /// descriptor.
+    pub(crate) struct RunReader<T> {
+        path: PathBuf,
+        remaining: usize,
+        reader: Option<BufReader<File>>,
+        _phantom: PhantomData<T>,
+    }
+
+    impl<T> RunReader<T>
+    where
+        T: Spill,
+    {
+        /// Writes the items to a new run file in `dir`.
+        pub(crate) fn create(dir: &Path, items: &[T]) -> io::Result<Self> {
+            Self::create_with(dir, |writer| {
+                for item in items {
+                    item.spill(writer)?;
+                }
+                Ok(items.len())
+            })
+        }
+
+        /// Writes the items to a new run file in `dir`, stopping at the first error.
+        pub(crate) fn create_from_iter<I>(dir: &Path, items: I) -> io::Result<Self>
+        where
+            I: IntoIterator<Item = io::Result<T>>,
+        {
+            Self::create_with(dir, |writer| {
+                let mut len = 0;
+                for item in items {
+                    item?.spill(writer)?;
+                    len += 1;
+                }
+                Ok(len)
+            })
+        }
+
+        fn create_with<F>(dir: &Path, write: F) -> io::Result<Self>
+        where
+            F: FnOnce(&mut BufWriter<File>) -> io::Result<usize>,
+        {
+            let run_id = NEXT_RUN_ID.fetch_add(1, SeqCst);
+            let path = dir.join(format!("par-stream-sort-{}-{}.run", process::id(), run_id));
+
+            let result = (|| {
+                let mut writer = BufWriter::new(File::create(&path)?);
+                let len = write(&mut writer)?;
+                writer.flush()?;
+                Ok(len)
+            })();
+
+            match result {
+                Ok(len) => Ok(Self {
+                    path,
+                    remaining: len,
+                    reader: None,
+                    _phantom: PhantomData,
+                }),
+                Err(err) => {
+                    let _ = fs::remove_file(&path);
+                    Err(err)
+                }
+            }
+        }
+
+        fn load(&mut self) -> io::Result<T> {
+            let reader = match &mut self.reader {
+                Some(reader) => reader,
+                None => self.reader.insert(BufReader::new(File::open(&self.path)?)),
+            };
+            T::load(reader)
+        }
+    }
+
+    impl<T> Iterator for RunReader<T>
+    where
+        T: Spill,
+    {
+        type Item = io::Result<T>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.remaining == 0 {
+                return None;
+            }
+
+            let result = self.load();
+            self.remaining = if result.is_ok() {
+                self.remaining - 1
+            } else {
+                0
+            };
+            Some(result)
+        }
+    }
+
+    impl<T> Drop for RunReader<T> {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+// merge_runs
+
+/// The maximum number of run files merged at a time.
+pub(crate) const MAX_FAN_IN: usize = 64;
+
+/// Merges the runs into one sorted sequence, reading at most `fan_in` run files at a time.
+///
+/// While there are more than `fan_in` runs, consecutive groups of `fan_in` runs are merged
+/// into new run files in `dir`. Consecutive runs are merged together to keep the result
+/// stable.
+pub(crate) fn merge_runs<T, F>(
+    dir: &Path,
+    mut runs: Vec<RunReader<T>>,
+    fan_in: usize,
+    cmp: F,
+) -> io::Result<KMerge<RunReader<T>, T, F>>
+where
+    T: Spill,
+    F: Fn(&T, &T) -> cmp::Ordering + Clone,
+{
+    assert!(fan_in >= 2, "fan_in must be at least 2");
+
+    while runs.len() > fan_in {
+        let mut groups = runs.into_iter().peekable();
+        let mut merged = vec![];
+
+        while groups.peek().is_some() {
+            let group: Vec<_> = groups.by_ref().take(fan_in).collect();
+            let run = if group.len() == 1 {
+                group.into_iter().next().unwrap()
+            } else {
+                RunReader::create_from_iter(dir, KMerge::new(group, cmp.clone()))?
+            };
+            merged.push(run);
+        }
+
+        runs = merged;
+    }
+
+    Ok(KMerge::new(runs, cmp))
+}
+
+// k_merge
+
+pub(crate) use k_merge::*;
+
+mod k_merge {
+    use super::*;
+
+    /// Merges sorted sources into one sorted sequence.
+    ///
+    /// Equal items are taken from the source with the smaller index first, so that
+    /// merging runs of a stable sort keeps the result stable. The iterator ends after
+    /// the first error from a source.
+    pub(crate) struct KMerge<I, T, F> {
+        sources: Vec<I>,
+        heads: Vec<Option<T>>,
+        /// A binary min-heap of source indices ordered by their head items.
+        heap: Vec<usize>,
+        cmp: F,
+        is_initialized: bool,
+        is_terminated: bool,
+        pending_error: Option<io::Error>,
+    }
+
+    impl<I, T, F> KMerge<I, T, F>
+    where
+        I: Iterator<Item = io::Result<T>>,
+        F: Fn(&T, &T) -> cmp::Ordering,
+    {
+        pub(crate) fn new(sources: Vec<I>, cmp: F) -> Self {
+            let heads = sources.iter().map(|_| None).collect();
+
+            Self {
+                sources,
+                heads,
+                heap: vec![],
+                cmp,
+                is_initialized: false,
+                is_terminated: false,
+                pending_error: None,
+            }
+        }
+
+        fn less(&self, lhs: usize, rhs: usize) -> bool {
+            let lhs_item = self.heads[lhs].as_ref().unwrap();
+            let rhs_item = self.heads[rhs].as_ref().unwrap();
+
+            match (self.cmp)(lhs_item, rhs_item) {
+                Less => true,
+                Equal => lhs < rhs,
+                Greater => false,
+            }
+        }
+
+        fn sift_up(&mut self, mut pos: usize) {
+            while pos > 0 {
+                let parent = (pos - 1) / 2;
+                if !self.less(self.heap[pos], self.heap[parent]) {
+                    break;
+                }
+                self.heap.swap(pos, parent);
+                pos = parent;
+            }
+        }
+
+        fn sift_down(&mut self, mut pos: usize) {
+            loop {
+                let left = pos * 2 + 1;
+                let right = left + 1;
+                let mut min = pos;
+
+                if left < self.heap.len() && self.less(self.heap[left], self.heap[min]) {
+                    min = left;
+                }
+                if right < self.heap.len() && self.less(self.heap[right], self.heap[min]) {
+                    min = right;
+                }
+                if min == pos {
+                    break;
+                }
+
+                self.heap.swap(pos, min);
+                pos = min;
+            }
+        }
+
+        fn initialize(&mut self) -> io::Result<()> {
+            for index in 0..self.sources.len() {
+                if let Some(item) = self.sources[index].next() {
+                    self.heads[index] = Some(item?);
+                    self.heap.push(index);
+                    self.sift_up(self.heap.len() - 1);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl<I, T, F> Iterator for KMerge<I, T, F>
+    where
+        I: Iterator<Item = io::Result<T>>,
+        F: Fn(&T, &T) -> cmp::Ordering,
+    {
+        type Item = io::Result<T>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.is_terminated {
+                return None;
+            }
+
+            if let Some(err) = self.pending_error.take() {
+                self.is_terminated = true;
+                return Some(Err(err));
+            }
+
+            if !self.is_initialized {
+                self.is_initialized = true;
+
+                if let Err(err) = self.initialize() {
+                    self.is_terminated = true;
+                    return Some(Err(err));
+                }
+            }
+
+            let top = match self.heap.first() {
+                Some(&top) => top,
+                None => {
+                    self.is_terminated = true;
+                    return None;
+                }
+            };
+            let item = self.heads[top].take().unwrap();
+
+            match self.sources[top].next() {
+                Some(Ok(next)) => {
+                    self.heads[top] = Some(next);
+                }
+                Some(Err(err)) => {
+                    // yield the item first and the error on the next call
+                    self.pending_error = Some(err);
+                    self.heap.swap_remove(0);
+                }
+                None => {
+                    self.heap.swap_remove(0);
+                }
+            }
+
+            if !self.heap.is_empty() {
+                self.sift_down(0);
+            }
+
+            Some(Ok(item))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn merge_runs_test() {
+        let dir = std::env::temp_dir().join(format!("par-stream-merge-test-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut rng = rand::thread_rng();
+        let values: Vec<(u8, u64)> = (0..1000).map(|index| (rng.gen(), index)).collect();
+        let mut expect = values.clone();
+        expect.sort_by_key(|&(key, _)| key);
+
+        // spill 20 runs, which is more than the fan-in
+        let runs: Vec<_> = values
+            .chunks(50)
+            .map(|chunk| {
+                let mut chunk = chunk.to_vec();
+                chunk.sort_by_key(|&(key, _)| key);
+                RunReader::create(&dir, &chunk).unwrap()
+            })
+            .collect();
+
+        let merge = merge_runs(&dir, runs, 3, |lhs: &(u8, u64), rhs| lhs.0.cmp(&rhs.0)).unwrap();
+
+        // the intermediate runs are removed and at most fan-in runs are left
+        assert!(fs::read_dir(&dir).unwrap().count() <= 3);
+
+        // the merge is stable
+        let sorted: Vec<_> = merge.collect::<io::Result<_>>().unwrap();
+        assert_eq!(sorted, expect);
+
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir(&dir).unwrap();
+    }
+}
@@ -5,7 +5,8 @@ use tokio::sync::Mutex;
 /// Stream for the [tee()](crate::par_stream::ParStreamExt::tee) method.
 ///
 /// Cloning this stream allocates a new channel for the new receiver, so that
-/// future copies of stream items are forwarded to the channel.
+/// future copies of stream items are forwarded to the channel. The forwarding
+/// worker is aborted once all copies of the stream are dropped.
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct Tee<T>
@@ -68,7 +69,8 @@ where
                         break;
                     }
                 }
-            });
+            })
+            .abort_on_drop();
 
             Arc::new(Mutex::new(Some(future)))
         };
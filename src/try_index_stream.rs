@@ -62,6 +62,7 @@ where
         TryReorderEnumerated {
             stream: self,
             commit: 0,
+            high_water_mark: 0,
             pending_error: None,
             is_terminated: false,
             buffer: HashMap::new(),
@@ -86,6 +87,7 @@ mod try_reorder_enumerated {
         S: ?Sized,
     {
         pub(super) commit: usize,
+        pub(super) high_water_mark: usize,
         pub(super) is_terminated: bool,
         pub(super) pending_error: Option<E>,
         pub(super) buffer: HashMap<usize, T>,
@@ -95,6 +97,13 @@ mod try_reorder_enumerated {
         pub(super) stream: S,
     }
 
+    impl<S, T, E> TryReorderEnumerated<S, T, E> {
+        /// Returns the maximum number of items that were buffered for reordering so far.
+        pub fn high_water_mark(&self) -> usize {
+            self.high_water_mark
+        }
+    }
+
     impl<S, T, E> Stream for TryReorderEnumerated<S, T, E>
     where
         S: Stream<Item = Result<(usize, T), E>>,
@@ -129,6 +138,8 @@ mod try_reorder_enumerated {
                                     "the index number {} appears more than once",
                                     index
                                 );
+                                *this.high_water_mark =
+                                    cmp::max(*this.high_water_mark, this.buffer.len());
                             }
                             Equal => {
                                 *this.commit += 1;
@@ -1,8 +1,11 @@
 use crate::{
+    cancel::CancellationToken,
     common::*,
-    config::{BufSize, ParParams},
-    par_stream::ParStreamExt as _,
-    rt,
+    config::{BufSize, ErrorPolicy, ParParams},
+    panic::{self, PanicPayload},
+    par_stream::{spawned_on, ParStreamExt as _},
+    retry::RetryPolicy,
+    rt::{self, Elapsed},
     stream::StreamExt as _,
     try_index_stream::TryIndexStreamExt as _,
     try_stream::{TakeUntilError, TryStreamExt as _},
@@ -14,6 +17,10 @@ use tokio::sync::broadcast;
 /// Stream for the [try_par_batching()](TryParStreamExt::try_par_batching) method.
 pub type TryParBatching<T, E> = TakeUntilError<RecvStream<'static, Result<T, E>>, T, E>;
 
+/// Errors paired with the indices of input items, returned by
+/// [try_par_for_each_with_error_policy()](TryParStreamExt::try_par_for_each_with_error_policy).
+pub type IndexedErrors<E> = Vec<(usize, E)>;
+
 /// The trait extends [TryStream](futures::stream::TryStream) types with parallel processing combinators.
 pub trait TryParStreamExt
 where
@@ -63,6 +70,126 @@ where
         F: 'static + FnMut(Self::Ok) -> Fut + Send,
         Fut: 'static + Future<Output = Result<U, Self::Error>> + Send;
 
+    /// Fallible stream combinator for [par_then_catch_unwind](crate::ParStreamExt::par_then_catch_unwind).
+    ///
+    /// The panic of a task is converted to an error by `From<PanicPayload>` and stops the stream.
+    fn try_par_then_catch_unwind<U, P, F, Fut>(
+        self,
+        params: P,
+        f: F,
+    ) -> BoxStream<'static, Result<U, Self::Error>>
+    where
+        Self::Error: From<PanicPayload>,
+        P: Into<ParParams>,
+        U: 'static + Send,
+        F: 'static + FnMut(Self::Ok) -> Fut + Send,
+        Fut: 'static + Future<Output = Result<U, Self::Error>> + Send;
+
+    /// Fallible stream combinator for [par_then_timeout](crate::ParStreamExt::par_then_timeout).
+    ///
+    /// A future that does not finish within `duration` is dropped and its [Elapsed] error
+    /// is converted by `From<Elapsed>`, which stops the stream.
+    fn try_par_then_timeout<U, P, F, Fut>(
+        self,
+        params: P,
+        duration: Duration,
+        f: F,
+    ) -> BoxStream<'static, Result<U, Self::Error>>
+    where
+        Self::Error: From<Elapsed>,
+        P: Into<ParParams>,
+        U: 'static + Send,
+        F: 'static + FnMut(Self::Ok) -> Fut + Send,
+        Fut: 'static + Future<Output = Result<U, Self::Error>> + Send;
+
+    /// Fallible stream combinator for [par_then](crate::ParStreamExt::par_then) that retries failed tasks.
+    ///
+    /// The future for an input item is re-created by `f` and re-run after it returns an error,
+    /// according to the [RetryPolicy]. The attempts are made on the same worker, and the output
+    /// respects the input order. The error of the last attempt stops the stream.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::{prelude::*, RetryPolicy};
+    /// use std::sync::{
+    ///     atomic::{AtomicUsize, Ordering::*},
+    ///     Arc,
+    /// };
+    ///
+    /// let attempts: Arc<Vec<_>> = Arc::new((0..10).map(|_| AtomicUsize::new(0)).collect());
+    ///
+    /// let vec: Vec<usize> = stream::iter(0..10)
+    ///     .map(Ok)
+    ///     .try_par_then_retry(None, RetryPolicy::new(3), move |value| {
+    ///         let attempts = attempts.clone();
+    ///
+    ///         async move {
+    ///             // fails on the first attempt of every item
+    ///             if attempts[value].fetch_add(1, SeqCst) == 0 {
+    ///                 Err("flaky")
+    ///             } else {
+    ///                 Ok(value)
+    ///             }
+    ///         }
+    ///     })
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(vec, (0..10).collect::<Vec<_>>());
+    /// # })
+    /// ```
+    fn try_par_then_retry<U, P, F, Fut>(
+        self,
+        params: P,
+        policy: RetryPolicy<Self::Error>,
+        f: F,
+    ) -> BoxStream<'static, Result<U, Self::Error>>
+    where
+        Self::Ok: Clone,
+        P: Into<ParParams>,
+        U: 'static + Send,
+        F: 'static + FnMut(Self::Ok) -> Fut + Send + Clone,
+        Fut: 'static + Future<Output = Result<U, Self::Error>> + Send;
+
+    /// Fallible stream combinator for [par_then](crate::ParStreamExt::par_then) with an [ErrorPolicy].
+    ///
+    /// Unlike [try_par_then()](TryParStreamExt::try_par_then), errors do not necessarily stop
+    /// the stream. Both `Ok` and `Err` items are yielded respecting the input order until the
+    /// `policy` decides to stop, and the error that triggers the stop is the last item.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::{prelude::*, ErrorPolicy};
+    ///
+    /// let vec: Vec<_> = stream::iter(0..6)
+    ///     .map(Ok)
+    ///     .try_par_then_with_error_policy(None, ErrorPolicy::MaxErrors(2), |value| async move {
+    ///         if value % 2 == 1 {
+    ///             Err(value)
+    ///         } else {
+    ///             Ok(value)
+    ///         }
+    ///     })
+    ///     .collect()
+    ///     .await;
+    ///
+    /// assert_eq!(vec, [Ok(0), Err(1), Ok(2), Err(3)]);
+    /// # })
+    /// ```
+    fn try_par_then_with_error_policy<U, P, F, Fut>(
+        self,
+        params: P,
+        policy: ErrorPolicy,
+        f: F,
+    ) -> BoxStream<'static, Result<U, Self::Error>>
+    where
+        P: Into<ParParams>,
+        U: 'static + Send,
+        F: 'static + FnMut(Self::Ok) -> Fut + Send,
+        Fut: 'static + Future<Output = Result<U, Self::Error>> + Send;
+
     /// Fallible stream combinator for [par_then_unordered](crate::ParStreamExt::par_then_unordered).
     fn try_par_then_unordered<U, P, F, Fut>(
         self,
@@ -87,6 +214,37 @@ where
         F: 'static + FnMut(Self::Ok) -> Func + Send,
         Func: 'static + FnOnce() -> Result<U, Self::Error> + Send;
 
+    /// Fallible stream combinator for [par_map_catch_unwind](crate::ParStreamExt::par_map_catch_unwind).
+    ///
+    /// The panic of a task is converted to an error by `From<PanicPayload>` and stops the stream.
+    fn try_par_map_catch_unwind<U, P, F, Func>(
+        self,
+        params: P,
+        f: F,
+    ) -> BoxStream<'static, Result<U, Self::Error>>
+    where
+        Self::Error: From<PanicPayload>,
+        P: Into<ParParams>,
+        U: 'static + Send,
+        F: 'static + FnMut(Self::Ok) -> Func + Send,
+        Func: 'static + FnOnce() -> Result<U, Self::Error> + Send;
+
+    /// Fallible stream combinator for [par_map](crate::ParStreamExt::par_map) with an [ErrorPolicy].
+    ///
+    /// It is the blocking counterpart of
+    /// [try_par_then_with_error_policy()](TryParStreamExt::try_par_then_with_error_policy).
+    fn try_par_map_with_error_policy<U, P, F, Func>(
+        self,
+        params: P,
+        policy: ErrorPolicy,
+        f: F,
+    ) -> BoxStream<'static, Result<U, Self::Error>>
+    where
+        P: Into<ParParams>,
+        U: 'static + Send,
+        F: 'static + FnMut(Self::Ok) -> Func + Send,
+        Func: 'static + FnOnce() -> Result<U, Self::Error> + Send;
+
     /// Fallible stream combinator for [par_map_unordered](crate::ParStreamExt::par_map_unordered).
     fn try_par_map_unordered<U, P, F, Func>(
         self,
@@ -110,6 +268,44 @@ where
         F: 'static + FnMut(Self::Ok) -> Fut + Send,
         Fut: 'static + Future<Output = Result<(), Self::Error>> + Send;
 
+    /// Fallible stream combinator for [par_for_each](crate::par_stream::ParStreamExt::par_for_each) with an [ErrorPolicy].
+    ///
+    /// The tasks keep running after errors until the `policy` decides to stop. Once it stops, no
+    /// more input is taken, and the in-flight tasks are dropped if
+    /// [abort_on_error](ParParams::abort_on_error) is set or left to run to completion otherwise.
+    /// The future returns all collected errors, each paired with the index of the input item,
+    /// sorted by the index.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::{prelude::*, ErrorPolicy};
+    ///
+    /// let result = stream::iter(0..6)
+    ///     .map(Ok)
+    ///     .try_par_for_each_with_error_policy(None, ErrorPolicy::Continue, |value| async move {
+    ///         if value % 2 == 1 {
+    ///             Err(value)
+    ///         } else {
+    ///             Ok(())
+    ///         }
+    ///     })
+    ///     .await;
+    ///
+    /// assert_eq!(result, Err(vec![(1, 1), (3, 3), (5, 5)]));
+    /// # })
+    /// ```
+    fn try_par_for_each_with_error_policy<P, F, Fut>(
+        self,
+        params: P,
+        policy: ErrorPolicy,
+        f: F,
+    ) -> BoxFuture<'static, Result<(), IndexedErrors<Self::Error>>>
+    where
+        P: Into<ParParams>,
+        F: 'static + FnMut(Self::Ok) -> Fut + Send,
+        Fut: 'static + Future<Output = Result<(), Self::Error>> + Send;
+
     /// Fallible stream combinator for [par_for_each_blocking](crate::par_stream::ParStreamExt::par_for_each_blocking).
     fn try_par_for_each_blocking<P, F, Func>(
         self,
@@ -120,6 +316,74 @@ where
         P: Into<ParParams>,
         F: 'static + FnMut(Self::Ok) -> Func + Send,
         Func: 'static + FnOnce() -> Result<(), Self::Error> + Send;
+
+    /// Fallible stream combinator for [par_reduce](crate::par_stream::ParStreamExt::par_reduce).
+    ///
+    /// The workers stop taking input items as soon as an `Err` is received from the stream
+    /// or returned by `reduce_fn`, and the future returns that error. It returns `Ok(None)` if
+    /// the stream is empty or the workers are cancelled.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let sum = stream::iter(1..=100)
+    ///     .map(Ok)
+    ///     .try_par_reduce(None, |lhs: u32, rhs| async move {
+    ///         lhs.checked_add(rhs).ok_or("overflow")
+    ///     })
+    ///     .await;
+    /// assert_eq!(sum, Ok(Some(5050)));
+    /// # })
+    /// ```
+    fn try_par_reduce<P, F, Fut>(
+        self,
+        params: P,
+        reduce_fn: F,
+    ) -> BoxFuture<'static, Result<Option<Self::Ok>, Self::Error>>
+    where
+        P: Into<ParParams>,
+        F: 'static + FnMut(Self::Ok, Self::Ok) -> Fut + Send + Clone,
+        Fut: 'static + Future<Output = Result<Self::Ok, Self::Error>> + Send;
+
+    /// Fallible stream combinator for [par_fold](crate::par_stream::ParStreamExt::par_fold).
+    ///
+    /// The partial values are combined in input order, and the future returns the first error
+    /// in input order, either from the stream or from `fold_fn`.
+    ///
+    /// # Panics
+    /// The `chunk_size` must be positive.
+    ///
+    /// ```rust
+    /// # par_stream::rt::block_on_executor(async move {
+    /// use futures::prelude::*;
+    /// use par_stream::prelude::*;
+    ///
+    /// let text = stream::iter(["a", "b", "c", "d"])
+    ///     .map(|text| Ok(text.to_string()))
+    ///     .try_par_fold(None, 2, String::new(), |lhs, rhs| async move {
+    ///         if lhs.len() + rhs.len() <= 10 {
+    ///             Ok(lhs + &rhs)
+    ///         } else {
+    ///             Err("too long")
+    ///         }
+    ///     })
+    ///     .await;
+    /// assert_eq!(text, Ok("abcd".to_string()));
+    /// # })
+    /// ```
+    fn try_par_fold<P, F, Fut>(
+        self,
+        params: P,
+        chunk_size: usize,
+        identity: Self::Ok,
+        fold_fn: F,
+    ) -> BoxFuture<'static, Result<Self::Ok, Self::Error>>
+    where
+        P: Into<ParParams>,
+        F: 'static + FnMut(Self::Ok, Self::Ok) -> Fut + Send + Clone,
+        Fut: 'static + Future<Output = Result<Self::Ok, Self::Error>> + Send;
 }
 
 impl<S, T, E> TryParStreamExt for S
@@ -183,14 +447,26 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
 
         let (input_tx, input_rx) = utils::channel(buf_size);
         let (output_tx, output_rx) = utils::channel(buf_size);
         let (terminate_tx, _) = broadcast::channel(1);
 
-        rt::spawn(async move {
-            let _ = self.map(Ok).forward(input_tx.into_sink()).await;
+        rt::spawn_on(runtime.as_ref(), {
+            let cancelled = cancel.cancelled();
+
+            async move {
+                let _ = self
+                    .take_until(cancelled)
+                    .map(Ok)
+                    .forward(input_tx.into_sink())
+                    .await;
+            }
         });
 
         (0..num_workers).for_each(move |worker_index| {
@@ -199,8 +475,9 @@ where
             let mut terminate_rx = terminate_tx.subscribe();
             let terminate_tx = terminate_tx.clone();
             let f = f.clone();
+            let cancelled = cancel.cancelled();
 
-            rt::spawn(async move {
+            rt::spawn_on(runtime.as_ref(), async move {
                 let _ = stream::repeat(())
                     .take_until(async move {
                         let _ = terminate_rx.recv().await;
@@ -225,6 +502,7 @@ where
                         },
                     )
                     .take_until_error()
+                    .take_until(cancelled)
                     .map(Ok)
                     .forward(output_tx.into_sink())
                     .await;
@@ -241,17 +519,134 @@ where
         F: 'static + FnMut(T) -> Fut + Send,
         Fut: 'static + Future<Output = Result<U, E>> + Send,
     {
+        let params = params.into();
+        let panic_policy = params.panic_policy;
+        let cancelled = params.cancel.clone().unwrap_or_default().cancelled();
+
+        self.take_until_error()
+            .enumerate()
+            .par_then_unordered(params, move |(index, input)| {
+                let fut = input.map(|input| panic::catch_unwind(|| f(input)));
+
+                async move {
+                    match panic::catch_unwind_future(fut?).await {
+                        Ok(Ok(output)) => Ok((index, Ok(output))),
+                        Ok(Err(err)) => Err(err),
+                        Err(payload) => Ok((index, Err(payload))),
+                    }
+                }
+            })
+            .try_reorder_enumerated()
+            .try_filter_map(move |result| future::ok(panic::apply_policy(panic_policy, result)))
+            .take_until(cancelled)
+            .boxed()
+    }
+
+    fn try_par_then_catch_unwind<U, P, F, Fut>(
+        self,
+        params: P,
+        mut f: F,
+    ) -> BoxStream<'static, Result<U, E>>
+    where
+        E: From<PanicPayload>,
+        P: Into<ParParams>,
+        U: 'static + Send,
+        F: 'static + FnMut(T) -> Fut + Send,
+        Fut: 'static + Future<Output = Result<U, E>> + Send,
+    {
+        let params = params.into();
+        let cancelled = params.cancel.clone().unwrap_or_default().cancelled();
+
         self.take_until_error()
             .enumerate()
             .par_then_unordered(params, move |(index, input)| {
-                let fut = input.map(|input| f(input));
+                let fut = input.map(|input| panic::catch_unwind(|| f(input)));
 
                 async move {
-                    let output = fut?.await?;
+                    let output = panic::catch_unwind_future(fut?).await??;
                     Ok((index, output))
                 }
             })
             .try_reorder_enumerated()
+            .take_until(cancelled)
+            .boxed()
+    }
+
+    fn try_par_then_timeout<U, P, F, Fut>(
+        self,
+        params: P,
+        duration: Duration,
+        mut f: F,
+    ) -> BoxStream<'static, Result<U, E>>
+    where
+        E: From<Elapsed>,
+        P: Into<ParParams>,
+        U: 'static + Send,
+        F: 'static + FnMut(T) -> Fut + Send,
+        Fut: 'static + Future<Output = Result<U, E>> + Send,
+    {
+        self.try_par_then(params, move |item| {
+            let fut = f(item);
+            async move { rt::timeout(duration, fut).await? }
+        })
+    }
+
+    fn try_par_then_retry<U, P, F, Fut>(
+        self,
+        params: P,
+        policy: RetryPolicy<E>,
+        f: F,
+    ) -> BoxStream<'static, Result<U, E>>
+    where
+        T: Clone,
+        P: Into<ParParams>,
+        U: 'static + Send,
+        F: 'static + FnMut(T) -> Fut + Send + Clone,
+        Fut: 'static + Future<Output = Result<U, E>> + Send,
+    {
+        self.try_par_then(params, move |item| {
+            let mut f = f.clone();
+            let policy = policy.clone();
+
+            async move {
+                let mut attempt = 1;
+
+                loop {
+                    match f(item.clone()).await {
+                        Ok(output) => break Ok(output),
+                        Err(err) if policy.should_retry(attempt, &err) => {
+                            rt::sleep(policy.delay(attempt)).await;
+                            attempt += 1;
+                        }
+                        Err(err) => break Err(err),
+                    }
+                }
+            }
+        })
+    }
+
+    fn try_par_then_with_error_policy<U, P, F, Fut>(
+        self,
+        params: P,
+        policy: ErrorPolicy,
+        mut f: F,
+    ) -> BoxStream<'static, Result<U, E>>
+    where
+        P: Into<ParParams>,
+        U: 'static + Send,
+        F: 'static + FnMut(T) -> Fut + Send,
+        Fut: 'static + Future<Output = Result<U, E>> + Send,
+    {
+        let stop = CancellationToken::new();
+
+        self.take_until(stop.cancelled())
+            .par_then(params, move |input| {
+                let fut = input.map(&mut f);
+                async move { fut?.await }
+            })
+            .scan(ErrorCounter::new(policy, stop), |counter, result| {
+                future::ready(counter.count(result))
+            })
             .boxed()
     }
 
@@ -287,17 +682,79 @@ where
         F: 'static + FnMut(T) -> Func + Send,
         Func: 'static + FnOnce() -> Result<U, E> + Send,
     {
+        let params = params.into();
+        let panic_policy = params.panic_policy;
+        let cancelled = params.cancel.clone().unwrap_or_default().cancelled();
+
         self.take_until_error()
             .enumerate()
             .par_map_unordered(params, move |(index, input)| {
-                let func = input.map(|input| f(input));
+                let func = input.map(|input| panic::catch_unwind(|| f(input)));
+
+                move || match func?.and_then(panic::catch_unwind) {
+                    Ok(Ok(output)) => Ok((index, Ok(output))),
+                    Ok(Err(err)) => Err(err),
+                    Err(payload) => Ok((index, Err(payload))),
+                }
+            })
+            .try_reorder_enumerated()
+            .try_filter_map(move |result| future::ok(panic::apply_policy(panic_policy, result)))
+            .take_until(cancelled)
+            .boxed()
+    }
+
+    fn try_par_map_catch_unwind<U, P, F, Func>(
+        self,
+        params: P,
+        mut f: F,
+    ) -> BoxStream<'static, Result<U, E>>
+    where
+        E: From<PanicPayload>,
+        P: Into<ParParams>,
+        U: 'static + Send,
+        F: 'static + FnMut(T) -> Func + Send,
+        Func: 'static + FnOnce() -> Result<U, E> + Send,
+    {
+        let params = params.into();
+        let cancelled = params.cancel.clone().unwrap_or_default().cancelled();
+
+        self.take_until_error()
+            .enumerate()
+            .par_map_unordered(params, move |(index, input)| {
+                let func = input.map(|input| panic::catch_unwind(|| f(input)));
 
                 move || {
-                    let output = (func?)()?;
+                    let output = func?.and_then(panic::catch_unwind)??;
                     Ok((index, output))
                 }
             })
             .try_reorder_enumerated()
+            .take_until(cancelled)
+            .boxed()
+    }
+
+    fn try_par_map_with_error_policy<U, P, F, Func>(
+        self,
+        params: P,
+        policy: ErrorPolicy,
+        mut f: F,
+    ) -> BoxStream<'static, Result<U, E>>
+    where
+        P: Into<ParParams>,
+        U: 'static + Send,
+        F: 'static + FnMut(T) -> Func + Send,
+        Func: 'static + FnOnce() -> Result<U, E> + Send,
+    {
+        let stop = CancellationToken::new();
+
+        self.take_until(stop.cancelled())
+            .par_map(params, move |input| {
+                let func = input.map(&mut f);
+                move || (func?)()
+            })
+            .scan(ErrorCounter::new(policy, stop), |counter, result| {
+                future::ready(counter.count(result))
+            })
             .boxed()
     }
 
@@ -335,38 +792,57 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            abort_on_error,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
+        let abort = CancellationToken::new();
         let (terminate_tx, mut terminate_rx) = broadcast::channel(1);
-        let input_stream = self
-            .take_until_error()
-            .take_until(async move {
-                let _ = terminate_rx.recv().await;
-            })
-            .stateful_map(f, |mut f, item| {
-                let fut = item.map(|item| f(item));
-                Some((f, fut))
-            })
-            .spawned(buf_size);
+        let input_stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            self.take_until_error()
+                .take_until(async move {
+                    let _ = terminate_rx.recv().await;
+                })
+                .take_until(cancel.cancelled())
+                .stateful_map(f, |mut f, item| {
+                    let fut = item.map(|item| f(item));
+                    Some((f, fut))
+                }),
+        );
 
         let worker_futures = (0..num_workers).map(move |_| {
             let terminate_tx = terminate_tx.clone();
 
-            rt::spawn(
+            rt::spawn_on(
+                runtime.as_ref(),
                 input_stream
                     .clone()
-                    .stateful_then(terminate_tx, |terminate_tx, fut| async move {
-                        let result = async move {
-                            fut?.await?;
-                            Ok(())
-                        }
-                        .await;
+                    .stateful_then(
+                        (terminate_tx, abort.clone()),
+                        move |(terminate_tx, abort), fut| async move {
+                            let result = async move {
+                                fut?.await?;
+                                Ok(())
+                            }
+                            .await;
 
-                        if result.is_err() {
-                            let _ = terminate_tx.send(());
-                        }
+                            if result.is_err() {
+                                let _ = terminate_tx.send(());
 
-                        Some((terminate_tx, result))
-                    })
+                                if abort_on_error {
+                                    abort.cancel();
+                                }
+                            }
+
+                            Some(((terminate_tx, abort), result))
+                        },
+                    )
+                    .take_until(cancel.cancelled())
+                    .take_until(abort.cancelled())
                     .try_for_each(|()| future::ok(())),
             )
         });
@@ -376,6 +852,72 @@ where
             .boxed()
     }
 
+    fn try_par_for_each_with_error_policy<P, F, Fut>(
+        self,
+        params: P,
+        policy: ErrorPolicy,
+        mut f: F,
+    ) -> BoxFuture<'static, Result<(), IndexedErrors<E>>>
+    where
+        P: Into<ParParams>,
+        F: 'static + FnMut(T) -> Fut + Send,
+        Fut: 'static + Future<Output = Result<(), E>> + Send,
+    {
+        let params = params.into();
+        let abort_on_error = params.abort_on_error;
+        let stop = CancellationToken::new();
+        let abort = CancellationToken::new();
+        let task_abort = abort.clone();
+
+        let mut results = self
+            .take_until(stop.cancelled())
+            .enumerate()
+            .par_then_unordered(params, move |(index, input)| {
+                let fut = input.map(&mut f);
+                let task = async move {
+                    let result = match fut {
+                        Ok(fut) => fut.await,
+                        Err(err) => Err(err),
+                    };
+                    result.map_err(|err| (index, err))
+                }
+                .boxed();
+
+                // the task is dropped once the policy stops with abort_on_error
+                future::select(task, task_abort.cancelled()).map(|output| match output {
+                    future::Either::Left((result, _)) => Some(result),
+                    future::Either::Right(_) => None,
+                })
+            })
+            .filter_map(future::ready);
+
+        async move {
+            let mut counter = ErrorCounter::new(policy, stop);
+            let mut errors = vec![];
+
+            while let Some(result) = results.next().await {
+                if let Some(Err(err)) = counter.count(result) {
+                    errors.push(err);
+                }
+
+                if counter.is_stopped {
+                    if abort_on_error {
+                        abort.cancel();
+                    }
+                    break;
+                }
+            }
+
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                errors.sort_by_key(|(index, _)| *index);
+                Err(errors)
+            }
+        }
+        .boxed()
+    }
+
     fn try_par_for_each_blocking<P, F, Func>(
         self,
         params: P,
@@ -389,25 +931,37 @@ where
         let ParParams {
             num_workers,
             buf_size,
+            cancel,
+            runtime,
+            ..
         } = params.into();
+        let cancel = cancel.unwrap_or_default();
         let (terminate_tx, mut terminate_rx) = broadcast::channel(1);
-        let stream = self
-            .take_until_error()
-            .take_until(async move {
-                let _ = terminate_rx.recv().await;
-            })
-            .stateful_map(f, |mut f, item| {
-                let fut = item.map(|item| f(item));
-                Some((f, fut))
-            })
-            .spawned(buf_size);
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            self.take_until_error()
+                .take_until(async move {
+                    let _ = terminate_rx.recv().await;
+                })
+                .take_until(cancel.cancelled())
+                .stateful_map(f, |mut f, item| {
+                    let fut = item.map(|item| f(item));
+                    Some((f, fut))
+                }),
+        );
 
         let worker_futures = (0..num_workers).map(|_| {
             let mut stream = stream.clone();
             let terminate_tx = terminate_tx.clone();
+            let cancel = cancel.clone();
 
-            rt::spawn_blocking(move || {
+            rt::spawn_blocking_on(runtime.as_ref(), move || {
                 while let Some(func) = rt::block_on(stream.next()) {
+                    if cancel.is_cancelled() {
+                        break;
+                    }
+
                     let result = (move || {
                         (func?)()?;
                         Ok(())
@@ -426,6 +980,175 @@ where
             .map(|result| result.map(|_| ()))
             .boxed()
     }
+
+    fn try_par_reduce<P, F, Fut>(
+        self,
+        params: P,
+        reduce_fn: F,
+    ) -> BoxFuture<'static, Result<Option<T>, E>>
+    where
+        P: Into<ParParams>,
+        F: 'static + FnMut(T, T) -> Fut + Send + Clone,
+        Fut: 'static + Future<Output = Result<T, E>> + Send,
+    {
+        let ParParams {
+            num_workers,
+            buf_size,
+            cancel,
+            runtime,
+            ..
+        } = params.into();
+        let cancel = cancel.unwrap_or_default();
+        let abort = CancellationToken::new();
+        let stream = spawned_on(
+            runtime.as_ref(),
+            buf_size,
+            self.take_until(abort.cancelled())
+                .take_until(cancel.cancelled()),
+        );
+
+        // phase 1
+        let phase_1_future = {
+            let reduce_fn = reduce_fn.clone();
+            let runtime = runtime.clone();
+            let reducer_futures = (0..num_workers).map(move |_| {
+                let mut reduce_fn = reduce_fn.clone();
+                let mut stream = stream.clone();
+                let abort = abort.clone();
+
+                rt::spawn_on(runtime.as_ref(), async move {
+                    let result = async {
+                        let mut acc = None;
+
+                        while let Some(item) = stream.next().await {
+                            let item = item?;
+                            acc = Some(match acc {
+                                Some(acc) => reduce_fn(acc, item).await?,
+                                None => item,
+                            });
+                        }
+
+                        Ok(acc)
+                    }
+                    .await;
+
+                    // stop other workers on error
+                    if result.is_err() {
+                        abort.cancel();
+                    }
+                    result
+                })
+            });
+
+            future::try_join_all(reducer_futures)
+        };
+
+        // phase 2
+        let phase_2_future = async move {
+            let mut values: Vec<_> = phase_1_future.await?.into_iter().flatten().collect();
+
+            // reduce the values pairwise in parallel
+            while values.len() > 1 {
+                let mut iter = values.into_iter();
+                let mut pair_futures = vec![];
+                let mut remaining = None;
+
+                while let Some(first) = iter.next() {
+                    match iter.next() {
+                        Some(second) => {
+                            let mut reduce_fn = reduce_fn.clone();
+                            pair_futures
+                                .push(rt::spawn_on(runtime.as_ref(), reduce_fn(first, second)));
+                        }
+                        None => remaining = Some(first),
+                    }
+                }
+
+                values = future::try_join_all(pair_futures).await?;
+                values.extend(remaining);
+            }
+
+            // discard the partially reduced value if the workers are cancelled
+            if cancel.is_cancelled() {
+                Ok(None)
+            } else {
+                Ok(values.pop())
+            }
+        };
+
+        phase_2_future.boxed()
+    }
+
+    fn try_par_fold<P, F, Fut>(
+        self,
+        params: P,
+        chunk_size: usize,
+        identity: T,
+        fold_fn: F,
+    ) -> BoxFuture<'static, Result<T, E>>
+    where
+        P: Into<ParParams>,
+        F: 'static + FnMut(T, T) -> Fut + Send + Clone,
+        Fut: 'static + Future<Output = Result<T, E>> + Send,
+    {
+        assert!(chunk_size > 0, "chunk_size must be positive");
+        let combine_fn = fold_fn.clone();
+
+        // reduce contiguous chunks in parallel
+        let partials = self.chunks(chunk_size).par_then(params, move |chunk| {
+            let mut fold_fn = fold_fn.clone();
+
+            async move {
+                let mut items = chunk.into_iter();
+                let first = items.next().unwrap()?;
+                stream::iter(items).try_fold(first, &mut fold_fn).await
+            }
+        });
+
+        // combine the partial values in input order
+        partials.try_fold(identity, combine_fn).boxed()
+    }
+}
+
+// error_counter
+
+/// Counts the items and errors, and stops the input once the [ErrorPolicy] decides.
+struct ErrorCounter {
+    policy: ErrorPolicy,
+    stop: CancellationToken,
+    num_items: usize,
+    num_errors: usize,
+    is_stopped: bool,
+}
+
+impl ErrorCounter {
+    fn new(policy: ErrorPolicy, stop: CancellationToken) -> Self {
+        Self {
+            policy,
+            stop,
+            num_items: 0,
+            num_errors: 0,
+            is_stopped: false,
+        }
+    }
+
+    fn count<T, E>(&mut self, result: Result<T, E>) -> Option<Result<T, E>> {
+        if self.is_stopped {
+            return None;
+        }
+
+        self.num_items += 1;
+        if result.is_err() {
+            self.num_errors += 1;
+        }
+
+        if self.policy.should_stop(self.num_items, self.num_errors) {
+            self.is_stopped = true;
+            self.stop.cancel();
+        }
+
+        Some(result)
+    }
 }
 
 // tests
@@ -433,7 +1156,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::async_test;
+    use crate::utils::{async_test, DropGuard};
     use rand::prelude::*;
 
     async_test! {
@@ -535,6 +1258,47 @@ mod tests {
 
                 assert_eq!(result, Err(-3));
             }
+
+            {
+                let params = ParParams {
+                    num_workers: 4,
+                    abort_on_error: true,
+                    ..ParParams::default()
+                };
+                let (mut guards, receivers): (Vec<_>, Vec<_>) = (0..4)
+                    .map(|_| DropGuard::new())
+                    .map(|(guard, rx)| (Some(guard), rx))
+                    .unzip();
+                let (started_tx, started_rx) = flume::unbounded();
+
+                let result = stream::iter(0..4)
+                    .map(Ok)
+                    .try_par_for_each(params, move |value| {
+                        let mut guard = guards[value].take().unwrap();
+                        let started_tx = started_tx.clone();
+                        let started_rx = started_rx.clone();
+
+                        async move {
+                            // fails once the other items are in flight
+                            if value == 0 {
+                                started_rx.stream().take(3).count().await;
+                                return Err(value);
+                            }
+
+                            started_tx.send(()).unwrap();
+                            rt::sleep(Duration::from_secs(5)).await;
+                            guard.finish();
+                            Ok(())
+                        }
+                    })
+                    .await;
+                assert_eq!(result, Err(0));
+
+                // the in-flight futures are dropped before they finish
+                for rx in receivers {
+                    assert_eq!(rx.await, Ok(false));
+                }
+            }
         }
 
 
@@ -625,6 +1389,234 @@ mod tests {
         }
 
 
+        async fn try_par_then_catch_unwind_test() {
+            let vec: Vec<Result<_, PanicPayload>> = stream::iter(1..)
+                .map(Ok)
+                .try_par_then_catch_unwind(3, |index| async move {
+                    assert!(index != 3, "unexpected index");
+                    Ok(index)
+                })
+                .collect()
+                .await;
+
+            assert!(matches!(
+                *vec,
+                [Err(_)] | [Ok(1), Err(_)] | [Ok(2), Err(_)] | [Ok(1), Ok(2), Err(_)],
+            ));
+            assert_eq!(
+                vec.last().unwrap().as_ref().unwrap_err().message(),
+                Some("unexpected index")
+            );
+        }
+
+
+        async fn try_par_then_timeout_test() {
+            #[derive(Debug, PartialEq)]
+            enum Error {
+                Timeout,
+            }
+
+            impl From<Elapsed> for Error {
+                fn from(_: Elapsed) -> Self {
+                    Self::Timeout
+                }
+            }
+
+            let vec: Vec<Result<_, Error>> = stream::iter([5u64, 5, 1000, 5])
+                .map(Ok)
+                .try_par_then_timeout(None, Duration::from_millis(100), |millis| async move {
+                    rt::sleep(Duration::from_millis(millis)).await;
+                    Ok(millis)
+                })
+                .collect()
+                .await;
+
+            assert_eq!(vec, [Ok(5), Ok(5), Err(Error::Timeout)]);
+        }
+
+
+        async fn try_par_then_retry_test() {
+            use std::sync::atomic::AtomicUsize;
+
+            let attempts: Arc<Vec<AtomicUsize>> = Arc::new((0..20).map(|_| AtomicUsize::new(0)).collect());
+
+            let vec: Vec<Result<_, usize>> = stream::iter(0..20)
+                .map(Ok)
+                .try_par_then_retry(None, RetryPolicy::new(3).retry_if(|&value| value != 15), {
+                    let attempts = attempts.clone();
+
+                    move |value| {
+                        let attempts = attempts.clone();
+
+                        async move {
+                            let attempt = attempts[value].fetch_add(1, SeqCst) + 1;
+
+                            // succeeds at the second attempt except the value 15
+                            if attempt < 2 || value == 15 {
+                                Err(value)
+                            } else {
+                                Ok(value)
+                            }
+                        }
+                    }
+                })
+                .collect()
+                .await;
+
+            let expect: Vec<_> = (0..15).map(Ok).chain([Err(15)]).collect();
+            assert_eq!(vec, expect);
+            assert_eq!(attempts[0].load(SeqCst), 2);
+            assert_eq!(attempts[15].load(SeqCst), 1);
+        }
+
+
+        async fn try_par_then_with_error_policy_test() {
+            let f = |value: usize| async move {
+                rt::sleep(Duration::from_millis(value as u64 % 5)).await;
+                if value % 3 == 0 {
+                    Err(value)
+                } else {
+                    Ok(value)
+                }
+            };
+
+            {
+                let vec: Vec<_> = stream::iter(0..10)
+                    .map(Ok)
+                    .try_par_then_with_error_policy(None, ErrorPolicy::Continue, f)
+                    .collect()
+                    .await;
+                let expect: Vec<_> = (0..10)
+                    .map(|value| if value % 3 == 0 { Err(value) } else { Ok(value) })
+                    .collect();
+                assert_eq!(vec, expect);
+            }
+
+            {
+                let vec: Vec<_> = stream::iter(0..10)
+                    .map(Ok)
+                    .try_par_then_with_error_policy(None, ErrorPolicy::MaxErrors(3), f)
+                    .collect()
+                    .await;
+                assert_eq!(vec, [Err(0), Ok(1), Ok(2), Err(3), Ok(4), Ok(5), Err(6)]);
+            }
+
+            {
+                // no limit on the number of errors
+                let vec: Vec<_> = stream::iter(0..10)
+                    .map(Ok)
+                    .try_par_then_with_error_policy(None, ErrorPolicy::MaxErrors(0), f)
+                    .collect()
+                    .await;
+                assert_eq!(vec.len(), 10);
+                assert_eq!(vec.iter().filter(|result| result.is_err()).count(), 4);
+            }
+
+            {
+                let policy = ErrorPolicy::ErrorRatio {
+                    ratio: 0.3,
+                    min_items: 4,
+                };
+                let vec: Vec<_> = stream::iter(0..10)
+                    .map(Ok)
+                    .try_par_then_with_error_policy(None, policy, f)
+                    .collect()
+                    .await;
+                assert_eq!(vec, [Err(0), Ok(1), Ok(2), Err(3)]);
+            }
+
+            {
+                let result = stream::iter(0..10)
+                    .map(Ok)
+                    .try_par_for_each_with_error_policy(None, ErrorPolicy::Continue, move |value| {
+                        f(value).map(|result| result.map(|_| ()))
+                    })
+                    .await;
+                assert_eq!(result, Err(vec![(0, 0), (3, 3), (6, 6), (9, 9)]));
+            }
+
+            {
+                let params = ParParams {
+                    num_workers: 4,
+                    abort_on_error: true,
+                    ..ParParams::default()
+                };
+                let (mut guards, receivers): (Vec<_>, Vec<_>) = (0..4)
+                    .map(|_| DropGuard::new())
+                    .map(|(guard, rx)| (Some(guard), rx))
+                    .unzip();
+                let (started_tx, started_rx) = flume::unbounded();
+
+                let result = stream::iter(0..4)
+                    .map(Ok)
+                    .try_par_for_each_with_error_policy(params, ErrorPolicy::FailFast, move |value| {
+                        let mut guard = guards[value].take().unwrap();
+                        let started_tx = started_tx.clone();
+                        let started_rx = started_rx.clone();
+
+                        async move {
+                            // fails once the other items are in flight
+                            if value == 0 {
+                                started_rx.stream().take(3).count().await;
+                                return Err(value);
+                            }
+
+                            started_tx.send(()).unwrap();
+                            rt::sleep(Duration::from_secs(5)).await;
+                            guard.finish();
+                            Ok(())
+                        }
+                    })
+                    .await;
+                assert_eq!(result, Err(vec![(0, 0)]));
+
+                // the in-flight futures are dropped before they finish
+                for rx in receivers {
+                    assert_eq!(rx.await, Ok(false));
+                }
+            }
+        }
+
+
+        async fn try_par_reduce_test() {
+            {
+                let sum = stream::iter(1..=10_000u64)
+                    .map(Ok::<_, ()>)
+                    .try_par_reduce(None, |lhs, rhs| async move { Ok(lhs + rhs) })
+                    .await;
+                assert_eq!(sum, Ok(Some(10_000 * 10_001 / 2)));
+            }
+
+            {
+                let sum = stream::iter(iter::empty::<Result<u64, ()>>())
+                    .try_par_reduce(None, |lhs, rhs| async move { Ok(lhs + rhs) })
+                    .await;
+                assert_eq!(sum, Ok(None));
+            }
+
+            {
+                let sum = stream::iter(0..10_000u64)
+                    .map(|value| if value == 5000 { Err(value) } else { Ok(value) })
+                    .try_par_reduce(None, |lhs, rhs| async move { Ok(lhs + rhs) })
+                    .await;
+                assert_eq!(sum, Err(5000));
+            }
+
+            {
+                let text = stream::iter(0..100)
+                    .map(|value| Ok(value.to_string()))
+                    .try_par_fold(None, 8, String::new(), |lhs, rhs| async move {
+                        if rhs.contains("50") {
+                            Err(rhs)
+                        } else {
+                            Ok(lhs + &rhs)
+                        }
+                    })
+                    .await;
+                assert_eq!(text, Err("50".to_string()));
+            }
+        }
+
         async fn try_reorder_enumerated_test() {
             let len: usize = 1000;
             let mut rng = rand::thread_rng();
@@ -16,7 +16,11 @@
 macro_rules! no_rt {
     ($($item:item)*) => {
         $(
-            #[cfg(all(not(feature = "runtime-async-std"), not(feature = "runtime-tokio"),))]
+            #[cfg(all(
+                not(feature = "runtime-async-std"),
+                not(feature = "runtime-tokio"),
+                not(feature = "runtime-smol"),
+            ))]
             $item
         )*
     };
@@ -26,7 +30,11 @@ pub(crate) use no_rt;
 macro_rules! has_tokio {
     ($($item:item)*) => {
         $(
-            #[cfg(all(not(feature = "runtime-async-std"), feature = "runtime-tokio",))]
+            #[cfg(all(
+                not(feature = "runtime-async-std"),
+                feature = "runtime-tokio",
+                not(feature = "runtime-smol"),
+            ))]
             $item
         )*
     };
@@ -36,27 +44,54 @@ pub(crate) use has_tokio;
 macro_rules! has_async_std {
     ($($item:item)*) => {
         $(
-            #[cfg(all(feature = "runtime-async-std", not(feature = "runtime-tokio"),))]
+            #[cfg(all(
+                feature = "runtime-async-std",
+                not(feature = "runtime-tokio"),
+                not(feature = "runtime-smol"),
+            ))]
             $item
         )*
     };
 }
 pub(crate) use has_async_std;
 
+macro_rules! has_smol {
+    ($($item:item)*) => {
+        $(
+            #[cfg(all(
+                not(feature = "runtime-async-std"),
+                not(feature = "runtime-tokio"),
+                feature = "runtime-smol",
+            ))]
+            $item
+        )*
+    };
+}
+pub(crate) use has_smol;
+
 #[allow(unused_macros)]
 macro_rules! async_test {
-    ($($item:item)*) => {
+    ($(async fn $name:ident() $body:block)*) => {
         crate::utils::has_tokio! {
             $(
                 #[tokio::test]
-                $item
+                async fn $name() $body
             )*
         }
 
         crate::utils::has_async_std! {
             $(
                 #[async_std::test]
-                $item
+                async fn $name() $body
+            )*
+        }
+
+        crate::utils::has_smol! {
+            $(
+                #[test]
+                fn $name() {
+                    crate::rt::block_on_executor(async move $body)
+                }
             )*
         }
     };
@@ -70,3 +105,36 @@ pub fn channel<T>(capacity: impl Into<Option<usize>>) -> (flume::Sender<T>, flum
         None => flume::unbounded(),
     }
 }
+
+/// Reports through a oneshot channel whether the owning future finished once it is dropped.
+#[cfg(test)]
+pub(crate) struct DropGuard {
+    finished: bool,
+    tx: Option<tokio::sync::oneshot::Sender<bool>>,
+}
+
+#[cfg(test)]
+impl DropGuard {
+    pub fn new() -> (Self, tokio::sync::oneshot::Receiver<bool>) {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let guard = Self {
+            finished: false,
+            tx: Some(tx),
+        };
+        (guard, rx)
+    }
+
+    /// Marks the owning future as finished.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+}
+
+#[cfg(test)]
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        if let Some(tx) = self.tx.take() {
+            let _ = tx.send(self.finished);
+        }
+    }
+}